@@ -0,0 +1,62 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use index_framework::{
+    backend::memory::postings::{compressed, default, packed},
+    traits::postings::{BuildPostings, IndexPostings},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+/// Sorted random postings with a few dense terms
+fn postings_map() -> HashMap<u32, Vec<u32>> {
+    let mut rand = StdRng::seed_from_u64(42);
+    (0..5000)
+        .map(|term| {
+            let size = if term % 100 == 0 {
+                20000
+            } else {
+                rand.gen_range(1..200)
+            };
+            let mut list: Vec<u32> = (0..size).map(|_| rand.gen_range(0..1_000_000)).collect();
+            list.sort_unstable();
+            (term, list)
+        })
+        .collect()
+}
+
+fn bench_postings<P>(c: &mut Criterion, name: &str, map: &HashMap<u32, Vec<u32>>)
+where
+    P: IndexPostings + BuildPostings<Output = P, PostingList = Vec<u32>>,
+{
+    let postings = P::from_map(map.clone());
+
+    c.bench_function(&format!("{name} get_posting"), |b| {
+        b.iter(|| {
+            for term in (0..5000).step_by(7) {
+                black_box(postings.get_posting(black_box(term)));
+            }
+        });
+    });
+
+    c.bench_function(&format!("{name} build"), |b| {
+        b.iter(|| P::from_map(black_box(map.clone())));
+    });
+}
+
+fn postings_formats(c: &mut Criterion) {
+    let map = postings_map();
+    bench_postings::<default::Postings>(c, "default", &map);
+    bench_postings::<compressed::Postings>(c, "compressed", &map);
+    bench_postings::<packed::Postings>(c, "packed", &map);
+
+    let packed = packed::Postings::from_map(map.clone());
+    c.bench_function("packed first_geq", |b| {
+        b.iter(|| {
+            for term in (0..5000).step_by(100) {
+                black_box(packed.first_geq(term, black_box(500_000)));
+            }
+        });
+    });
+}
+
+criterion_group!(benches, postings_formats);
+criterion_main!(benches);
@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+use unicode_normalization::UnicodeNormalization;
+
+/// Modifies single tokens produced by a tokenizer
+pub trait TokenFilter: Send + Sync {
+    /// Returns the modified token or `None` if the token should be dropped
+    fn apply(&self, token: String) -> Option<String>;
+}
+
+/// Lowercases tokens
+pub struct LowercaseFilter;
+
+impl TokenFilter for LowercaseFilter {
+    #[inline]
+    fn apply(&self, token: String) -> Option<String> {
+        Some(token.to_lowercase())
+    }
+}
+
+/// Unicode normalization forms
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+/// Brings tokens into a unicode normalization form
+pub struct NormalizeFilter {
+    form: NormalizationForm,
+}
+
+impl NormalizeFilter {
+    #[inline]
+    pub fn new(form: NormalizationForm) -> Self {
+        Self { form }
+    }
+}
+
+impl TokenFilter for NormalizeFilter {
+    #[inline]
+    fn apply(&self, token: String) -> Option<String> {
+        let token = match self.form {
+            NormalizationForm::Nfc => token.nfc().collect(),
+            NormalizationForm::Nfd => token.nfd().collect(),
+            NormalizationForm::Nfkc => token.nfkc().collect(),
+            NormalizationForm::Nfkd => token.nfkd().collect(),
+        };
+        Some(token)
+    }
+}
+
+/// Drops tokens contained in a list of stopwords
+pub struct StopwordFilter {
+    stopwords: HashSet<String>,
+}
+
+impl StopwordFilter {
+    #[inline]
+    pub fn new<I, U>(stopwords: I) -> Self
+    where
+        I: IntoIterator<Item = U>,
+        U: Into<String>,
+    {
+        let stopwords = stopwords.into_iter().map(|i| i.into()).collect();
+        Self { stopwords }
+    }
+}
+
+impl TokenFilter for StopwordFilter {
+    #[inline]
+    fn apply(&self, token: String) -> Option<String> {
+        (!self.stopwords.contains(&token)).then_some(token)
+    }
+}
+
+/// Drops tokens with less than `min` or more than `max` characters
+pub struct LengthFilter {
+    min: usize,
+    max: usize,
+}
+
+impl LengthFilter {
+    #[inline]
+    pub fn new(min: usize, max: usize) -> Self {
+        Self { min, max }
+    }
+}
+
+impl TokenFilter for LengthFilter {
+    #[inline]
+    fn apply(&self, token: String) -> Option<String> {
+        let len = token.chars().count();
+        (len >= self.min && len <= self.max).then_some(token)
+    }
+}
@@ -0,0 +1,52 @@
+pub mod filter;
+pub mod tokenizer;
+
+use filter::{LowercaseFilter, TokenFilter};
+use tokenizer::{Tokenizer, UnicodeWordTokenizer};
+
+/// Turns raw text into terms by splitting it with a tokenizer and passing each token through a
+/// chain of filters. Use the same analyzer for indexing and querying so both produce matching
+/// terms
+pub struct Analyzer {
+    tokenizer: Box<dyn Tokenizer>,
+    filters: Vec<Box<dyn TokenFilter>>,
+}
+
+impl Analyzer {
+    /// Creates a new analyzer without filters
+    #[inline]
+    pub fn new<T: Tokenizer + 'static>(tokenizer: T) -> Self {
+        Self {
+            tokenizer: Box::new(tokenizer),
+            filters: vec![],
+        }
+    }
+
+    /// Appends a filter. Filters are applied in the order they were added
+    #[inline]
+    pub fn with_filter<F: TokenFilter + 'static>(mut self, filter: F) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Returns the terms of the given text
+    pub fn analyze(&self, text: &str) -> Vec<String> {
+        self.tokenizer
+            .tokenize(text)
+            .into_iter()
+            .filter_map(|token| {
+                self.filters
+                    .iter()
+                    .try_fold(token, |token, filter| filter.apply(token))
+            })
+            .collect()
+    }
+}
+
+impl Default for Analyzer {
+    /// Splits text at unicode word boundaries and lowercases all words
+    #[inline]
+    fn default() -> Self {
+        Self::new(UnicodeWordTokenizer).with_filter(LowercaseFilter)
+    }
+}
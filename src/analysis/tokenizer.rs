@@ -0,0 +1,67 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Splits text into tokens
+pub trait Tokenizer: Send + Sync {
+    fn tokenize(&self, text: &str) -> Vec<String>;
+}
+
+/// Splits text at whitespace
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    #[inline]
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        text.split_whitespace().map(|i| i.to_string()).collect()
+    }
+}
+
+/// Splits text at unicode word boundaries (UAX #29) and drops punctuation and whitespace
+pub struct UnicodeWordTokenizer;
+
+impl Tokenizer for UnicodeWordTokenizer {
+    #[inline]
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        text.unicode_words().map(|i| i.to_string()).collect()
+    }
+}
+
+/// Splits text into overlapping n-grams of `n` characters. With padding, the text is surrounded
+/// by `n - 1` padding characters on each side, so the start and end of the text get their own
+/// n-grams. Without padding, text shorter than `n` characters is returned as single token
+pub struct NGramTokenizer {
+    n: usize,
+    padding: Option<char>,
+}
+
+impl NGramTokenizer {
+    #[inline]
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "n-grams need at least one character");
+        Self { n, padding: None }
+    }
+
+    /// Pads the text with the given character
+    #[inline]
+    pub fn with_padding(mut self, padding: char) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+}
+
+impl Tokenizer for NGramTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        let mut chars: Vec<char> = text.chars().collect();
+        if chars.is_empty() {
+            return vec![];
+        }
+
+        if let Some(padding) = self.padding {
+            let pad = std::iter::repeat_n(padding, self.n - 1);
+            chars = pad.clone().chain(chars).chain(pad).collect();
+        } else if chars.len() <= self.n {
+            return vec![text.to_string()];
+        }
+
+        chars.windows(self.n).map(|i| i.iter().collect()).collect()
+    }
+}
@@ -1,11 +1,18 @@
-use super::{dict::default::Dictionary, postings::compressed::Postings, storage::default::Storage};
-use crate::traits::{
-    backend::{Backend, NewBackend},
-    deser::DeSer,
-    dict_item::DictItem,
+use super::{
+    dict::default::Dictionary, postings::compressed::Postings, storage::default::Storage,
+    FORMAT_VERSION,
+};
+use crate::{
+    error::Error,
+    stats::IndexStats,
+    traits::{
+        backend::{Backend, NewBackend},
+        deser::DeSer,
+        dict_item::DictItem,
+    },
 };
 use serde::{Deserialize, Serialize};
-use std::io::Read;
+use std::io::{Read, Write};
 
 /// Completely in memory index backend
 #[derive(Serialize, Deserialize, Default)]
@@ -13,6 +20,7 @@ pub struct MemoryBackend<T, S> {
     dict: Dictionary<T>,
     postings_list: Vec<Postings>,
     storage: Storage<S>,
+    stats: IndexStats,
 }
 
 impl<T, S> Backend<T, S> for MemoryBackend<T, S>
@@ -24,16 +32,28 @@ where
     type Postings = Postings;
     type Storage = Storage<S>;
 
-    fn decode_from<R: Read>(reader: R) -> Option<Self>
+    fn decode_from<R: Read>(mut reader: R) -> Result<Self, Error>
     where
         Self: Sized,
     {
-        bincode::deserialize_from(reader).ok()
+        let found: u32 = bincode::deserialize_from(&mut reader)?;
+        if found != FORMAT_VERSION {
+            return Err(Error::VersionMismatch {
+                expected: FORMAT_VERSION,
+                found,
+            });
+        }
+        Ok(bincode::deserialize_from(reader)?)
+    }
+
+    #[inline]
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serialize(&(FORMAT_VERSION, self))?)
     }
 
     #[inline]
-    fn encode(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+    fn encode_into<W: Write>(&self, out: W) -> Result<(), Error> {
+        Ok(bincode::serialize_into(out, &(FORMAT_VERSION, self))?)
     }
 
     #[inline]
@@ -51,6 +71,11 @@ where
         &self.storage
     }
 
+    #[inline]
+    fn stats(&self) -> Option<&IndexStats> {
+        Some(&self.stats)
+    }
+
     #[inline]
     fn posting_count(&self) -> usize {
         self.postings_list.len()
@@ -68,6 +93,12 @@ where
             dict,
             postings_list,
             storage,
+            stats: IndexStats::default(),
         }
     }
+
+    #[inline]
+    fn set_stats(&mut self, stats: IndexStats) {
+        self.stats = stats;
+    }
 }
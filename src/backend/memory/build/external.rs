@@ -0,0 +1,323 @@
+use super::options::BuildOption;
+use crate::{
+    error::Error,
+    stats::IndexStats,
+    traits::{
+        backend::{Backend, NewBackend},
+        build::IndexBuilder,
+        deser::DeSer,
+        dict_item::DictItem,
+        dictionary::BuildIndexDictionary,
+        postings::BuildPostings,
+        storage::BuildIndexStorage,
+    },
+    Index,
+};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fs::File,
+    hash::Hash,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    mem::size_of,
+};
+
+/// A single `(postings ID, term ID, item ID)` mapping
+type Entry = (u32, u32, u32);
+
+/// Index builder keeping the postings out of memory. Mapped items are buffered until the memory
+/// budget is reached, then sorted and written as run into a temporary file. `build` k-way merges
+/// all runs and creates one postings-list after another.
+///
+/// Postings are always sorted. Term positions are not supported
+pub struct ExternalIndexBuilder<B, T, S, DD, SS, PP> {
+    dict: DD,
+    storage: SS,
+    term_map: HashMap<T, u32>,
+    /// Mappings that weren't written into a run yet
+    buf: Vec<Entry>,
+    /// Maximum amount of buffered mappings
+    buf_limit: usize,
+    runs: Vec<File>,
+    postings_len: usize,
+    stats: IndexStats,
+    options: Vec<BuildOption>,
+    /// First error that occurred while encoding a term or item or writing a run in `map`
+    error: Option<Error>,
+    p: PhantomData<(B, S, PP)>,
+}
+
+impl<B, T, S, DD, SS, PP> ExternalIndexBuilder<B, T, S, DD, SS, PP>
+where
+    B: Backend<T, S> + NewBackend<T, S>,
+    T: DictItem + Hash + Clone,
+    S: DeSer,
+    DD: BuildIndexDictionary<T, Output = B::Dict>,
+    SS: BuildIndexStorage<S, Output = B::Storage>,
+    PP: BuildPostings<Output = B::Postings, PostingList = Vec<u32>>,
+{
+    /// Create a new index builder buffering at most `memory_budget` bytes of postings
+    #[inline]
+    pub fn new(memory_budget: usize) -> Self {
+        Self::with_postings_len(1, memory_budget)
+    }
+
+    /// Create a new index builder with custom amount of postings buffering at most
+    /// `memory_budget` bytes of postings
+    pub fn with_postings_len(postings_len: usize, memory_budget: usize) -> Self {
+        if postings_len < 1 {
+            panic!("At least one postings required!");
+        }
+
+        Self {
+            dict: DD::new(),
+            storage: SS::new(),
+            term_map: HashMap::new(),
+            buf: vec![],
+            buf_limit: (memory_budget / size_of::<Entry>()).max(1),
+            runs: vec![],
+            postings_len,
+            stats: IndexStats::with_postings_len(postings_len),
+            options: vec![],
+            error: None,
+            p: PhantomData,
+        }
+    }
+
+    /// Adds a build-option to the builder. `SortedPostings` is always applied
+    #[inline]
+    pub fn add_option(&mut self, option: BuildOption) {
+        self.options.push(option)
+    }
+
+    /// Returns the dictionary value
+    #[inline]
+    pub fn dict(&self) -> &DD {
+        &self.dict
+    }
+
+    /// Returns the storage value
+    #[inline]
+    pub fn storage(&self) -> &SS {
+        &self.storage
+    }
+
+    /// Returns a map of terms to its IDs
+    #[inline]
+    pub fn term_map(&self) -> &HashMap<T, u32> {
+        &self.term_map
+    }
+
+    /// Returns the statistics of all mapped items
+    #[inline]
+    pub fn stats(&self) -> &IndexStats {
+        &self.stats
+    }
+
+    /// Returns the amount of runs written to disk so far
+    #[inline]
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Sorts the buffered mappings and writes them into a new temporary run file
+    fn flush(&mut self) -> Result<(), Error> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+
+        self.buf.sort_unstable();
+
+        let mut file = tempfile::tempfile()?;
+        {
+            let mut out = BufWriter::new(&mut file);
+            for (p_id, t_id, item) in self.buf.drain(..) {
+                out.write_all(&p_id.to_le_bytes())?;
+                out.write_all(&t_id.to_le_bytes())?;
+                out.write_all(&item.to_le_bytes())?;
+            }
+            out.flush()?;
+        }
+        file.seek(SeekFrom::Start(0))?;
+
+        self.runs.push(file);
+        Ok(())
+    }
+
+    /// Merges all runs and builds the postings
+    fn build_postings(&mut self) -> Result<Vec<B::Postings>, Error> {
+        let unique = self.options.contains(&BuildOption::UniquePostings);
+
+        // Small indexes don't need to touch the disk at all
+        let mut runs: Vec<Run> = if self.runs.is_empty() {
+            self.buf.sort_unstable();
+            vec![Run::Mem(std::mem::take(&mut self.buf).into_iter())]
+        } else {
+            self.flush()?;
+            self.runs
+                .drain(..)
+                .map(|f| Run::File(BufReader::new(f)))
+                .collect()
+        };
+
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (i, run) in runs.iter_mut().enumerate() {
+            if let Some(entry) = run.next_entry()? {
+                heap.push(Reverse((entry, i)));
+            }
+        }
+
+        // Entries are sorted by postings ID, so each postings gets built and its map dropped
+        // as soon as the merge moves past it
+        let mut out: Vec<B::Postings> = Vec::with_capacity(self.postings_len);
+        let mut map: HashMap<u32, Vec<u32>> = HashMap::new();
+        let mut last: Option<Entry> = None;
+        let mut list: Vec<u32> = vec![];
+
+        while let Some(Reverse((entry, run))) = heap.pop() {
+            if let Some(next) = runs[run].next_entry()? {
+                heap.push(Reverse((next, run)));
+            }
+
+            let (p_id, t_id, item) = entry;
+            if unique && last == Some(entry) {
+                continue;
+            }
+
+            if let Some((l_pid, l_tid, _)) = last {
+                if (l_pid, l_tid) != (p_id, t_id) {
+                    map.insert(l_tid, std::mem::take(&mut list));
+                }
+            }
+            // Finish all postings up to the current one
+            while out.len() < p_id as usize {
+                out.push(PP::from_map(std::mem::take(&mut map)).build());
+            }
+
+            list.push(item);
+            last = Some(entry);
+        }
+
+        if let Some((_, l_tid, _)) = last {
+            map.insert(l_tid, list);
+        }
+
+        while out.len() < self.postings_len {
+            out.push(PP::from_map(std::mem::take(&mut map)).build());
+        }
+        Ok(out)
+    }
+
+    /// Generates the index. Returns the first error that occurred while encoding terms and items,
+    /// writing runs in `map` or merging them
+    pub fn try_build(mut self) -> Result<Index<B, T, S>, Error> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+
+        self.dict.finish();
+
+        let postings = self.build_postings()?;
+        let dict = self.dict.build();
+        let storage = self.storage.build();
+
+        let mut backend = B::new(dict, postings, storage);
+        backend.set_stats(self.stats);
+        Ok(Index::new(backend))
+    }
+}
+
+impl<B, T, S, DD, SS, PP> IndexBuilder<T, S> for ExternalIndexBuilder<B, T, S, DD, SS, PP>
+where
+    B: Backend<T, S> + NewBackend<T, S>,
+    T: DictItem + Hash + Clone,
+    S: DeSer,
+    DD: BuildIndexDictionary<T, Output = B::Dict>,
+    SS: BuildIndexStorage<S, Output = B::Storage>,
+    PP: BuildPostings<Output = B::Postings, PostingList = Vec<u32>>,
+{
+    type ForBackend = B;
+
+    fn insert_term(&mut self, term: T) -> Result<u32, u32> {
+        if let Some(id) = self.term_map.get(&term) {
+            return Err(*id);
+        }
+
+        // Terms that can't be encoded get the ID they would have had. The error is surfaced by
+        // `try_build`
+        let id = match self.dict.insert(term.clone()) {
+            Ok(id) => id,
+            Err(err) => {
+                self.error.get_or_insert(err);
+                return Ok(self.term_map.len() as u32);
+            }
+        };
+        self.term_map.insert(term, id);
+        Ok(id)
+    }
+
+    #[inline]
+    fn insert_item(&mut self, item: S) -> u32 {
+        self.storage.insert(item).unwrap_or_else(|err| {
+            self.error.get_or_insert(err);
+            self.storage.len() as u32
+        })
+    }
+
+    fn map(&mut self, postings_id: u32, item: u32, terms: &[u32]) {
+        if postings_id as usize >= self.postings_len {
+            panic!(
+                "Invalid postings index {} of {}",
+                postings_id, self.postings_len,
+            );
+        }
+
+        for term in terms {
+            self.buf.push((postings_id, *term, item));
+            if self.buf.len() >= self.buf_limit {
+                // Surfaced by `try_build`
+                if let Err(err) = self.flush() {
+                    self.error.get_or_insert(err);
+                }
+            }
+        }
+
+        if let Some(stats) = self.stats.posting_mut(postings_id) {
+            stats.add(item, terms.len() as u32);
+        }
+    }
+
+    /// Generates the index. Panics if encoding a term or item or writing or reading the runs
+    /// failed, use `try_build` to handle these errors
+    #[inline]
+    fn build(self) -> Index<Self::ForBackend, T, S> {
+        self.try_build().expect("Failed to build the index")
+    }
+}
+
+/// A sorted run of mappings
+enum Run {
+    Mem(std::vec::IntoIter<Entry>),
+    File(BufReader<File>),
+}
+
+impl Run {
+    /// Returns the next mapping of the run
+    fn next_entry(&mut self) -> Result<Option<Entry>, Error> {
+        let reader = match self {
+            Run::Mem(iter) => return Ok(iter.next()),
+            Run::File(reader) => reader,
+        };
+
+        let mut buf = [0u8; 12];
+        match reader.read_exact(&mut buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let num = |i: usize| u32::from_le_bytes(buf[i..i + 4].try_into().unwrap());
+        Ok(Some((num(0), num(4), num(8))))
+    }
+}
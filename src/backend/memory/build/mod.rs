@@ -1,12 +1,17 @@
+pub mod external;
 pub mod options;
+pub mod parallel;
 
+use super::dict::fixed_len::FixDict;
 use crate::{
+    error::Error,
+    stats::IndexStats,
     traits::{
         backend::{Backend, NewBackend},
         build::IndexBuilder,
         deser::DeSer,
         dict_item::DictItem,
-        dictionary::BuildIndexDictionary,
+        dictionary::{BuildIndexDictionary, IndexDictionary, NGramDictionary},
         postings::BuildPostings,
         storage::BuildIndexStorage,
     },
@@ -21,8 +26,13 @@ pub struct MemIndexBuilder<B, T, S, DD, SS, PP> {
     pub storage: SS,
     pub postings_list: Vec<HashMap<u32, Vec<u32>>>,
     pub term_map: HashMap<T, u32>,
+    /// Positions of terms within items as postings -> term -> item -> positions
+    positions: Vec<HashMap<u32, HashMap<u32, Vec<u32>>>>,
+    stats: IndexStats,
     options: Vec<BuildOption>,
     postings_mod: PostingsMod<B, T, S, DD, SS, PP>,
+    /// First error that occurred while inserting a term or item
+    error: Option<Error>,
     s: PhantomData<S>,
     b: PhantomData<B>,
     p: PhantomData<PP>,
@@ -54,13 +64,18 @@ where
         let storage = SS::new();
         let postings_list: Vec<_> = (0..postings_len).map(|_| HashMap::new()).collect();
         let term_map = HashMap::new();
+        let positions = (0..postings_len).map(|_| HashMap::new()).collect();
+        let stats = IndexStats::with_postings_len(postings_len);
         Self {
             dict,
             storage,
             postings_list,
             term_map,
+            positions,
+            stats,
             options: vec![],
             postings_mod: PostingsMod::default(),
+            error: None,
             s: PhantomData,
             b: PhantomData,
             p: PhantomData,
@@ -103,6 +118,12 @@ where
         &self.term_map
     }
 
+    /// Returns the statistics of all mapped items
+    #[inline]
+    pub fn stats(&self) -> &IndexStats {
+        &self.stats
+    }
+
     /// Get a mutable postings item by its ID
     #[inline]
     pub fn postings_mut(&mut self, pos: usize) -> Option<&mut HashMap<u32, Vec<u32>>> {
@@ -128,6 +149,7 @@ where
 
     fn build_postings(&mut self) -> Vec<<B as Backend<T, S>>::Postings> {
         let postings_list = std::mem::take(&mut self.postings_list);
+        let mut positions = std::mem::take(&mut self.positions);
         let sort = self.has_option(&BuildOption::SortedPostings);
 
         let mut postings_list_out = Vec::with_capacity(postings_list.len());
@@ -151,13 +173,98 @@ where
                 tmp_map.insert(t_id, ids);
             }
 
-            postings_list_out.push(PP::from_map(tmp_map).build());
+            let mut postings = PP::from_map(tmp_map);
+            let post_positions = std::mem::take(&mut positions[post_id]);
+            if !post_positions.is_empty() {
+                postings.set_positions(post_positions);
+            }
+
+            postings_list_out.push(postings.build());
         }
 
         postings_list_out
     }
 }
 
+impl<B, T, S, DD, SS, PP> MemIndexBuilder<B, T, S, DD, SS, PP>
+where
+    B: Backend<T, S> + NewBackend<T, S>,
+    T: DictItem + Hash + Clone,
+    S: DeSer,
+    DD: BuildIndexDictionary<T, Output = B::Dict>,
+    SS: BuildIndexStorage<S, Output = B::Storage>,
+    PP: BuildPostings<Output = B::Postings, PostingList = Vec<u32>>,
+{
+    /// Generates the index. Returns the first error that occurred while encoding a term or item
+    pub fn try_build(mut self) -> Result<Index<B, T, S>, Error> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+
+        self.dict.finish();
+
+        let postings = self.build_postings();
+        let dict = self.dict.build();
+        let storage = self.storage.build();
+
+        let mut backend = B::new(dict, postings, storage);
+        backend.set_stats(self.stats);
+        Ok(Index::new(backend))
+    }
+
+    /// Inserts an item into the index and maps it to the given terms, using the order of the
+    /// terms as their positions
+    pub fn index_with_positions<I, U>(&mut self, pst_id: u32, item: S, term_iter: I) -> u32
+    where
+        I: IntoIterator<Item = U>,
+        U: Into<T>,
+    {
+        let item_id = self.insert_item(item);
+        let terms: Vec<_> = self.terms_to_ids(term_iter).into_iter().zip(0..).collect();
+        self.map_positioned(pst_id, item_id, &terms);
+        item_id
+    }
+}
+
+impl<const N: usize, B, S, SS, PP> MemIndexBuilder<B, String, S, FixDict<N>, SS, PP>
+where
+    B: Backend<String, S, Dict = FixDict<N>> + NewBackend<String, S>,
+    S: DeSer,
+    SS: BuildIndexStorage<S, Output = B::Storage>,
+    PP: BuildPostings<Output = B::Postings, PostingList = Vec<u32>>,
+{
+    /// Sets the character text gets padded with when generating n-grams. The padding is stored in
+    /// the dictionary so queries generate the same n-grams. Has to be set before indexing items
+    #[inline]
+    pub fn set_ngram_padding(&mut self, padding: Option<char>) {
+        assert!(
+            self.dict.is_empty(),
+            "Padding can't be changed after n-grams were indexed"
+        );
+        self.dict.set_padding(padding);
+    }
+}
+
+impl<B, S, DD, SS, PP> MemIndexBuilder<B, String, S, DD, SS, PP>
+where
+    B: Backend<String, S> + NewBackend<String, S>,
+    S: DeSer,
+    DD: BuildIndexDictionary<String, Output = B::Dict> + NGramDictionary,
+    SS: BuildIndexStorage<S, Output = B::Storage>,
+    PP: BuildPostings<Output = B::Postings, PostingList = Vec<u32>>,
+{
+    /// Inserts an item into the index and maps it to the distinct n-grams of `text`, so the item
+    /// length in the index stats is its n-gram count. Returns the ID of the new item
+    pub fn index_ngrams(&mut self, pst_id: u32, item: S, text: &str) -> u32 {
+        let item_id = self.insert_item(item);
+        let mut terms = self.terms_to_ids(self.dict.ngrams(text));
+        terms.sort_unstable();
+        terms.dedup();
+        self.map(pst_id, item_id, &terms);
+        item_id
+    }
+}
+
 impl<B, T, S, DD, SS, PP> IndexBuilder<T, S> for MemIndexBuilder<B, T, S, DD, SS, PP>
 where
     B: Backend<T, S> + NewBackend<T, S>,
@@ -174,7 +281,15 @@ where
             return Err(*id);
         }
 
-        let id = self.dict.insert(term.clone());
+        // Terms that can't be encoded get the ID they would have had. The error is surfaced by
+        // `try_build`
+        let id = match self.dict.insert(term.clone()) {
+            Ok(id) => id,
+            Err(err) => {
+                self.error.get_or_insert(err);
+                return Ok(self.term_map.len() as u32);
+            }
+        };
 
         self.term_map.insert(term, id);
 
@@ -183,7 +298,10 @@ where
 
     #[inline]
     fn insert_item(&mut self, item: S) -> u32 {
-        self.storage.insert(item)
+        self.storage.insert(item).unwrap_or_else(|err| {
+            self.error.get_or_insert(err);
+            self.storage.len() as u32
+        })
     }
 
     #[inline]
@@ -211,15 +329,30 @@ where
 
             entry.push(item);
         }
-    }
 
-    fn build(mut self) -> Index<Self::ForBackend, T, S> {
-        self.dict.finish();
+        if let Some(stats) = self.stats.posting_mut(postings_id) {
+            stats.add(item, terms.len() as u32);
+        }
+    }
 
-        let postings = self.build_postings();
-        let dict = self.dict.build();
-        let storage = self.storage.build();
+    /// Maps an item to term-ids along with the position of each term within the item.
+    /// `terms` contains `(term_id, position)` pairs. The positions are only kept by postings
+    /// that support them
+    fn map_positioned(&mut self, postings_id: u32, item: u32, terms: &[(u32, u32)]) {
+        let term_ids: Vec<_> = terms.iter().map(|i| i.0).collect();
+        self.map(postings_id, item, &term_ids);
+
+        let positions = &mut self.positions[postings_id as usize];
+        for (term, pos) in terms {
+            let term_pos = positions.entry(*term).or_default();
+            term_pos.entry(item).or_default().push(*pos);
+        }
+    }
 
-        Index::new(B::new(dict, postings, storage))
+    /// Generates the index. Panics if a term or item couldn't be encoded, use `try_build` to
+    /// handle encoding errors
+    #[inline]
+    fn build(self) -> Index<Self::ForBackend, T, S> {
+        self.try_build().expect("Failed to encode a term or item")
     }
 }
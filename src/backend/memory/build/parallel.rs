@@ -0,0 +1,300 @@
+use super::{options::BuildOption, MemIndexBuilder};
+use crate::{
+    error::Error,
+    traits::{
+        backend::{Backend, NewBackend},
+        build::IndexBuilder,
+        deser::DeSer,
+        dict_item::DictItem,
+        dictionary::BuildIndexDictionary,
+        postings::BuildPostings,
+        storage::BuildIndexStorage,
+    },
+    Index,
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+/// Index builder accepting documents from multiple threads. Each thread indexes into its own
+/// shard with a thread-local term map and postings. At `build` the shards get merged ordered by
+/// the documents sequence numbers, so term and item IDs are the same as if the documents were
+/// indexed one after another in that order, regardless of thread scheduling.
+///
+/// Term positions are not supported
+pub struct ParallelIndexBuilder<B, T, S, DD, SS, PP> {
+    inner: MemIndexBuilder<B, T, S, DD, SS, PP>,
+    shards: Mutex<Vec<Shard<T, S>>>,
+    /// Sequence number of the next document indexed by `index_par`
+    next_seq: u64,
+}
+
+impl<B, T, S, DD, SS, PP> ParallelIndexBuilder<B, T, S, DD, SS, PP>
+where
+    B: Backend<T, S> + NewBackend<T, S>,
+    T: DictItem + Hash + Clone + Send,
+    S: DeSer + Send,
+    DD: BuildIndexDictionary<T, Output = B::Dict>,
+    SS: BuildIndexStorage<S, Output = B::Storage>,
+    PP: BuildPostings<Output = B::Postings, PostingList = Vec<u32>>,
+{
+    /// Create a new parallel index builder
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_postings_len(1)
+    }
+
+    /// Create a new parallel index builder with custom amount of postings
+    #[inline]
+    pub fn with_postings_len(postings_len: usize) -> Self {
+        Self {
+            inner: MemIndexBuilder::with_postings_len(postings_len),
+            shards: Mutex::new(vec![]),
+            next_seq: 0,
+        }
+    }
+
+    /// Adds a build-option to the builder
+    #[inline]
+    pub fn add_option(&mut self, option: BuildOption) {
+        self.inner.add_option(option)
+    }
+
+    /// Returns a new shard to index documents from a single thread. The documents get merged
+    /// once the writer is dropped
+    #[inline]
+    pub fn shard(&self) -> ShardWriter<'_, T, S> {
+        let postings_len = self.inner.postings_count();
+        ShardWriter {
+            term_map: HashMap::new(),
+            shard: Shard {
+                postings: vec![HashMap::new(); postings_len],
+                ..Shard::default()
+            },
+            unique: self.inner.has_option(&BuildOption::UniquePostings),
+            target: &self.shards,
+        }
+    }
+
+    /// Indexes all documents using `threads` threads. Documents are `(postings ID, item, terms)`
+    /// and get sequence numbers in iteration order, following the ones of previous calls
+    pub fn index_par<I>(&mut self, docs: I, threads: usize)
+    where
+        I: IntoIterator<Item = (u32, S, Vec<T>)>,
+    {
+        let docs: Vec<_> = docs.into_iter().collect();
+        let start = self.next_seq;
+        self.next_seq += docs.len() as u64;
+
+        let chunk_len = docs.len().div_ceil(threads.max(1)).max(1);
+        let mut docs = docs.into_iter();
+        thread::scope(|s| {
+            for seq in (start..self.next_seq).step_by(chunk_len) {
+                let chunk: Vec<_> = docs.by_ref().take(chunk_len).collect();
+                let mut writer = self.shard();
+                s.spawn(move || {
+                    for (seq, (pst_id, item, terms)) in (seq..).zip(chunk) {
+                        writer.index(seq, pst_id, item, terms);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Merges all shards and generates the index. Panics if a term or item couldn't be encoded,
+    /// use `try_build` to handle encoding errors
+    #[inline]
+    pub fn build(self) -> Index<B, T, S> {
+        self.try_build().expect("Failed to encode a term or item")
+    }
+
+    /// Merges all shards and generates the index on the calling thread. Returns the first error
+    /// that occurred while encoding a term or item
+    pub fn try_build(self) -> Result<Index<B, T, S>, Error> {
+        let mut inner = self.inner;
+        let mut shards = self
+            .shards
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut item_ids: Vec<Vec<u32>> = shards.iter().map(|i| vec![0; i.docs.len()]).collect();
+
+        // Items get their IDs in the order of the documents sequence numbers
+        let mut docs: Vec<_> = shards
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(s_id, shard)| {
+                let docs = shard.docs.drain(..).enumerate();
+                docs.map(move |(pos, doc)| (s_id, pos, doc))
+            })
+            .collect();
+        docs.sort_by_key(|(s_id, _, doc)| (doc.seq, *s_id));
+
+        for (s_id, pos, doc) in docs {
+            let item_id = inner.insert_item(doc.item);
+            item_ids[s_id][pos] = item_id;
+            if let Some(stats) = inner.stats.posting_mut(doc.postings_id) {
+                stats.add(item_id, doc.len);
+            }
+        }
+
+        // Terms get their IDs in the order of their first occurrence
+        let mut terms: Vec<_> = shards
+            .iter()
+            .enumerate()
+            .flat_map(|(s_id, shard)| {
+                let first = shard.first_seen.iter().enumerate();
+                first.map(move |(local, first)| (*first, s_id, local))
+            })
+            .collect();
+        terms.sort_unstable();
+
+        let mut term_ids: Vec<Vec<u32>> = shards.iter().map(|i| vec![0; i.terms.len()]).collect();
+        for (_, s_id, local) in terms {
+            let term = shards[s_id].terms[local].clone();
+            term_ids[s_id][local] = inner.insert_term(term).unwrap_or_else(|v| v);
+        }
+
+        for (s_id, shard) in shards.into_iter().enumerate() {
+            for (p_id, lists) in shard.postings.into_iter().enumerate() {
+                let postings = &mut inner.postings_list[p_id];
+                for (local, docs) in lists {
+                    let list = postings.entry(term_ids[s_id][local as usize]).or_default();
+                    list.extend(docs.into_iter().map(|i| item_ids[s_id][i as usize]));
+                }
+            }
+        }
+
+        // Lists were concatenated shard by shard. Sorting them restores the order they would
+        // have when indexing the documents one after another
+        for list in inner.postings_list.iter_mut().flat_map(|i| i.values_mut()) {
+            list.sort_unstable();
+        }
+
+        inner.try_build()
+    }
+}
+
+impl<B, T, S, DD, SS, PP> Default for ParallelIndexBuilder<B, T, S, DD, SS, PP>
+where
+    B: Backend<T, S> + NewBackend<T, S>,
+    T: DictItem + Hash + Clone + Send,
+    S: DeSer + Send,
+    DD: BuildIndexDictionary<T, Output = B::Dict>,
+    SS: BuildIndexStorage<S, Output = B::Storage>,
+    PP: BuildPostings<Output = B::Postings, PostingList = Vec<u32>>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Documents indexed by a single thread
+struct Shard<T, S> {
+    /// Terms ordered by their local IDs
+    terms: Vec<T>,
+    /// Sequence number and position of the first occurrence of each term
+    first_seen: Vec<(u64, u32)>,
+    docs: Vec<Doc<S>>,
+    /// Local term IDs mapped to the positions of the documents in `docs` for each postings ID
+    postings: Vec<HashMap<u32, Vec<u32>>>,
+}
+
+impl<T, S> Default for Shard<T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            terms: vec![],
+            first_seen: vec![],
+            docs: vec![],
+            postings: vec![],
+        }
+    }
+}
+
+/// A document indexed into a shard
+struct Doc<S> {
+    seq: u64,
+    postings_id: u32,
+    item: S,
+    /// Amount of terms the document got mapped to
+    len: u32,
+}
+
+/// Writer indexing documents into a single shard of a `ParallelIndexBuilder`
+pub struct ShardWriter<'a, T, S> {
+    term_map: HashMap<T, u32>,
+    shard: Shard<T, S>,
+    unique: bool,
+    target: &'a Mutex<Vec<Shard<T, S>>>,
+}
+
+impl<'a, T, S> ShardWriter<'a, T, S>
+where
+    T: DictItem + Hash + Clone,
+{
+    /// Indexes an item with the given terms. `seq` determines the position of the document in
+    /// the final index and has to be unique across all shards
+    pub fn index<I, U>(&mut self, seq: u64, postings_id: u32, item: S, terms: I)
+    where
+        I: IntoIterator<Item = U>,
+        U: Into<T>,
+    {
+        let shard = &mut self.shard;
+        let postings_count = shard.postings.len();
+        let postings = match shard.postings.get_mut(postings_id as usize) {
+            Some(postings) => postings,
+            None => panic!("Invalid postings index {postings_id} of {postings_count}"),
+        };
+
+        let doc = shard.docs.len() as u32;
+        let mut len = 0;
+        for (pos, term) in terms.into_iter().enumerate() {
+            let term = term.into();
+            let first = (seq, pos as u32);
+            let id = match self.term_map.get(&term) {
+                Some(id) => {
+                    let first_seen = &mut shard.first_seen[*id as usize];
+                    *first_seen = first.min(*first_seen);
+                    *id
+                }
+                None => {
+                    let id = shard.terms.len() as u32;
+                    shard.terms.push(term.clone());
+                    shard.first_seen.push(first);
+                    self.term_map.insert(term, id);
+                    id
+                }
+            };
+
+            len += 1;
+            let list = postings.entry(id).or_default();
+            // Documents get added in order, so an earlier occurrence has to be the last entry
+            if self.unique && list.last() == Some(&doc) {
+                continue;
+            }
+            list.push(doc);
+        }
+
+        shard.docs.push(Doc {
+            seq,
+            postings_id,
+            item,
+            len,
+        });
+    }
+}
+
+impl<'a, T, S> Drop for ShardWriter<'a, T, S> {
+    #[inline]
+    fn drop(&mut self) {
+        let shard = std::mem::take(&mut self.shard);
+        // A panic while pushing another shard poisons the lock but leaves the list intact, so
+        // this shard still gets merged
+        let mut shards = self.target.lock().unwrap_or_else(PoisonError::into_inner);
+        shards.push(shard);
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
+    error::Error,
     traits::{
         build::ItemMod,
         dict_item::DictItem,
-        dictionary::{BuildIndexDictionary, IndexDictionary},
+        dictionary::{BuildIndexDictionary, IndexDictionary, IndexDictionaryMod},
+        kind::Kind,
     },
     utils::bin_search::generic_binary_search,
 };
@@ -12,7 +14,7 @@ use st_file::{
     traits::{IndexedAccess, IndexedAccessMut},
     MemFile,
 };
-use std::marker::PhantomData;
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
 
 /// In memory dictionary
 #[derive(Serialize, Deserialize, Default)]
@@ -39,12 +41,12 @@ impl<T: DictItem> Dictionary<T> {
     }
 
     /// Inserts an item into the dictionary. `reorder` has to be called afterwards
-    pub(crate) fn insert_raw(&mut self, i: T) -> u32 {
-        let enc = i.encode_vec();
+    pub(crate) fn insert_raw(&mut self, i: T) -> Result<u32, Error> {
+        let enc = i.encode_vec()?;
         let item_id = self.data.insert(&enc);
         assert_eq!(self.data.len() - 1, item_id);
         self.sort_index.push(item_id as u32);
-        item_id as u32
+        Ok(item_id as u32)
     }
 
     /// Brings the item mapping back in order. Has to be called if changes were made
@@ -60,6 +62,10 @@ impl<T: DictItem> Dictionary<T> {
     }
 }
 
+impl<T> Kind for Dictionary<T> {
+    const KIND: &'static str = "dict.default";
+}
+
 impl<T: DictItem> IndexDictionary<T> for Dictionary<T> {
     #[inline]
     fn get_id<F: Into<T>>(&self, term: F) -> Option<u32> {
@@ -78,7 +84,7 @@ impl<T: DictItem> IndexDictionary<T> for Dictionary<T> {
 
     #[inline]
     fn get_term(&self, id: u32) -> Option<T> {
-        T::decode_vec(self.data.get(id as usize)?)
+        T::decode_vec(self.data.get(id as usize)?).ok()
     }
 
     #[inline]
@@ -86,12 +92,75 @@ impl<T: DictItem> IndexDictionary<T> for Dictionary<T> {
         (self.data.len() as u32) > id
     }
 
+    #[inline]
+    fn sort_pos(&self, pos: usize) -> Option<u32> {
+        self.sort_index.get(pos)
+    }
+
     #[inline]
     fn len(&self) -> usize {
         self.data.len()
     }
 }
 
+impl<T: DictItem + Clone + Hash> IndexDictionaryMod<T> for Dictionary<T> {
+    fn insert_terms(&mut self, terms: Vec<T>) -> Result<Vec<u32>, Error> {
+        // Lookups only work while the sort index is in order, so new terms are kept aside and
+        // get inserted at the end
+        let first_id = self.data.len() as u32;
+        let mut pending: HashMap<T, u32> = HashMap::new();
+        let ids = terms
+            .into_iter()
+            .map(|term| {
+                if let Some(id) = self.get_id(term.clone()) {
+                    return id;
+                }
+                let next_id = first_id + pending.len() as u32;
+                *pending.entry(term).or_insert(next_id)
+            })
+            .collect();
+
+        if pending.is_empty() {
+            return Ok(ids);
+        }
+
+        // Binary search the sort position of each new term among the existing ones
+        let mut new: Vec<(T, u32)> = pending.into_iter().collect();
+        new.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let positions: Vec<(usize, u32)> = new
+            .iter()
+            .map(|(term, id)| (self.lower_bound(term.clone()), *id))
+            .collect();
+
+        // Encode all terms upfront so a failing one leaves the dictionary untouched
+        new.sort_unstable_by_key(|i| i.1);
+        let encoded = new
+            .into_iter()
+            .map(|(term, id)| Ok((term.encode_vec()?, id)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        for (enc, id) in encoded {
+            let item_id = self.data.insert(&enc);
+            assert_eq!(item_id as u32, id);
+        }
+
+        // Merge the new IDs into the sort index without comparing any existing terms
+        let mut positions = positions.into_iter().peekable();
+        let mut sort_index = CVec::new();
+        for (pos, id) in self.sort_index.as_vec().into_iter().enumerate() {
+            while let Some((_, new_id)) = positions.next_if(|i| i.0 == pos) {
+                sort_index.push(new_id);
+            }
+            sort_index.push(id);
+        }
+        for (_, new_id) in positions {
+            sort_index.push(new_id);
+        }
+        self.sort_index = sort_index;
+
+        Ok(ids)
+    }
+}
+
 impl<T: DictItem> BuildIndexDictionary<T> for Dictionary<T> {
     type Output = Self;
 
@@ -101,7 +170,7 @@ impl<T: DictItem> BuildIndexDictionary<T> for Dictionary<T> {
     }
 
     #[inline]
-    fn insert(&mut self, i: T) -> u32 {
+    fn insert(&mut self, i: T) -> Result<u32, Error> {
         self.insert_raw(i)
     }
 
@@ -132,7 +201,7 @@ impl<'a, T: DictItem> MultInsert<'a, T> {
     }
 
     #[inline]
-    pub fn insert(&mut self, item: T) -> u32 {
+    pub fn insert(&mut self, item: T) -> Result<u32, Error> {
         self.dict.insert_raw(item)
     }
 }
@@ -149,9 +218,10 @@ where
     T: DictItem,
 {
     #[inline]
-    fn set_item(&mut self, id: u32, new: T) {
-        let encoded = new.encode_vec();
+    fn set_item(&mut self, id: u32, new: T) -> Result<(), Error> {
+        let encoded = new.encode_vec()?;
         self.data.replace(id as usize, &encoded);
+        Ok(())
     }
 }
 
@@ -170,7 +240,7 @@ mod test {
         {
             let mut push = dict.multi_push();
             for d in inpdict {
-                let id = push.insert(d.to_string());
+                let id = push.insert(d.to_string()).unwrap();
                 map.insert(d, id);
             }
         }
@@ -183,4 +253,106 @@ mod test {
             assert!(dict.has_term(&a.to_string()));
         }
     }
+
+    #[test]
+    pub fn test_sorted_lookups() {
+        let mut dict = Dictionary::<String>::new();
+        {
+            let mut push = dict.multi_push();
+            for d in ["aal", "auch", "auto", "make", "think", "zoo"] {
+                push.insert(d.to_string()).unwrap();
+            }
+        }
+
+        let prefix: Vec<_> = dict.terms_with_prefix("au").map(|i| i.1).collect();
+        assert_eq!(prefix, vec!["auch", "auto"]);
+        let rev: Vec<_> = dict.terms_with_prefix("").rev().map(|i| i.1).collect();
+        assert_eq!(rev, vec!["zoo", "think", "make", "auto", "auch", "aal"]);
+
+        let range: Vec<_> = dict.range("b".to_string()..="think".to_string()).collect();
+        assert_eq!(
+            range,
+            vec![(3, "make".to_string()), (4, "think".to_string())]
+        );
+
+        assert_eq!(dict.lower_bound("auf"), 2);
+        assert_eq!(dict.predecessor("auch"), Some((0, "aal".to_string())));
+        assert_eq!(dict.successor("zoo"), None);
+        assert_eq!(dict.successor("n"), Some((4, "think".to_string())));
+    }
+
+    #[test]
+    pub fn test_insert_terms() {
+        let mut dict = Dictionary::<String>::new();
+        {
+            let mut push = dict.multi_push();
+            for d in ["auch", "make", "zoo"] {
+                push.insert(d.to_string()).unwrap();
+            }
+        }
+
+        let terms = ["zz", "make", "a", "zz", "nah", "b"].map(|i| i.to_string());
+        let ids = dict.insert_terms(terms.to_vec()).unwrap();
+        assert_eq!(ids, vec![3, 1, 4, 3, 5, 6]);
+
+        for (id, term) in ids.iter().zip(terms) {
+            assert_eq!(dict.get_id(term), Some(*id));
+        }
+        let sorted: Vec<_> = dict.terms_with_prefix("").map(|i| i.1).collect();
+        assert_eq!(sorted, vec!["a", "auch", "b", "make", "nah", "zoo", "zz"]);
+    }
+
+    #[test]
+    pub fn test_fuzzy_terms() {
+        let words = [
+            "haus",
+            "hause",
+            "maus",
+            "mais",
+            "hass",
+            "h",
+            "",
+            "hausaufgabe",
+            "auto",
+            "zoo",
+            "häus",
+        ];
+
+        let mut dict = Dictionary::<String>::new();
+        {
+            let mut push = dict.multi_push();
+            for d in words {
+                push.insert(d.to_string()).unwrap();
+            }
+        }
+
+        for query in ["haus", "hau", "zo", "", "xyz"] {
+            for max_dist in 0..3 {
+                let mut res = dict.fuzzy_terms(query, max_dist);
+                res.sort_unstable();
+
+                let exp: Vec<_> = words
+                    .iter()
+                    .enumerate()
+                    .map(|(id, w)| (id as u32, levenshtein(query, w)))
+                    .filter(|i| i.1 <= max_dist)
+                    .collect();
+                assert_eq!(res, exp, "{query} {max_dist}");
+            }
+        }
+    }
+
+    fn levenshtein(a: &str, b: &str) -> u32 {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<u32> = (0..=b.len() as u32).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut next = vec![i as u32 + 1];
+            for (j, cb) in b.iter().enumerate() {
+                let cost = (ca != *cb) as u32;
+                next.push((row[j] + cost).min(row[j + 1] + 1).min(next[j] + 1));
+            }
+            row = next;
+        }
+        row[b.len()]
+    }
 }
@@ -1,7 +1,10 @@
 use crate::{
+    analysis::tokenizer::{NGramTokenizer, Tokenizer},
+    error::Error,
     traits::{
         build::ItemMod,
-        dictionary::{BuildIndexDictionary, IndexDictionary},
+        dictionary::{BuildIndexDictionary, IndexDictionary, NGramDictionary},
+        kind::Kind,
     },
     utils::bin_search::generic_binary_search,
     utils::const_arr_deser,
@@ -16,6 +19,8 @@ pub struct FixDict<const N: usize> {
     #[serde(with = "const_arr_deser")]
     data: Vec<[char; N]>,
     sort_index: CVec,
+    /// Character the text was padded with when generating the n-grams
+    padding: Option<char>,
 }
 
 impl<const N: usize> FixDict<N> {
@@ -24,9 +29,23 @@ impl<const N: usize> FixDict<N> {
         Self {
             data: vec![],
             sort_index: CVec::new(),
+            padding: None,
         }
     }
 
+    /// Returns the character text gets padded with when generating n-grams
+    #[inline]
+    pub fn padding(&self) -> Option<char> {
+        self.padding
+    }
+
+    /// Sets the character text gets padded with when generating n-grams. Has to be set before
+    /// indexing any item
+    #[inline]
+    pub fn set_padding(&mut self, padding: Option<char>) {
+        self.padding = padding;
+    }
+
     /// Allows pushing multiple items safely
     #[inline]
     pub fn multi_push(&mut self) -> MultInsert<N> {
@@ -67,10 +86,17 @@ impl<const N: usize> FixDict<N> {
     }
 }
 
+impl<const N: usize> Kind for FixDict<N> {
+    const KIND: &'static str = "dict.fixed_len";
+}
+
 impl<const N: usize> IndexDictionary<String> for FixDict<N> {
     #[inline]
     fn get_id<F: Into<String>>(&self, term: F) -> Option<u32> {
         let term = term.into();
+        if term.chars().count() < N {
+            return None;
+        }
         let t_chars = Self::char_array(&term);
 
         let mut buf_read = BufCVecRef::new(&self.sort_index);
@@ -98,12 +124,32 @@ impl<const N: usize> IndexDictionary<String> for FixDict<N> {
         (self.data.len() as u32) > id
     }
 
+    #[inline]
+    fn sort_pos(&self, pos: usize) -> Option<u32> {
+        self.sort_index.get(pos)
+    }
+
     #[inline]
     fn len(&self) -> usize {
         self.data.len()
     }
 }
 
+impl<const N: usize> NGramDictionary for FixDict<N> {
+    /// Returns the n-grams of `text` the way they are stored in the dictionary. Text too short to
+    /// fill a single unpadded n-gram has no n-grams
+    fn ngrams(&self, text: &str) -> Vec<String> {
+        let mut tokenizer = NGramTokenizer::new(N);
+        if let Some(padding) = self.padding {
+            tokenizer = tokenizer.with_padding(padding);
+        }
+
+        let mut ngrams = tokenizer.tokenize(text);
+        ngrams.retain(|i| i.chars().count() == N);
+        ngrams
+    }
+}
+
 impl<const N: usize> BuildIndexDictionary<String> for FixDict<N> {
     type Output = Self;
 
@@ -112,9 +158,19 @@ impl<const N: usize> BuildIndexDictionary<String> for FixDict<N> {
         Self::new()
     }
 
+    /// Keeps the n-gram padding, which has to be the same in both dictionaries
+    fn for_merge(a: &Self, b: &Self) -> Result<Self, Error> {
+        if a.padding != b.padding {
+            return Err(Error::SettingsMismatch("n-gram padding"));
+        }
+        let mut dict = Self::new();
+        dict.padding = a.padding;
+        Ok(dict)
+    }
+
     #[inline]
-    fn insert(&mut self, i: String) -> u32 {
-        self.insert_raw(i)
+    fn insert(&mut self, i: String) -> Result<u32, Error> {
+        Ok(self.insert_raw(i))
     }
 
     #[inline]
@@ -135,11 +191,12 @@ impl<const N: usize> BuildIndexDictionary<String> for FixDict<N> {
 
 impl<const N: usize> ItemMod<String> for FixDict<N> {
     #[inline]
-    fn set_item(&mut self, id: u32, new: String) {
+    fn set_item(&mut self, id: u32, new: String) -> Result<(), Error> {
         let chars = Self::char_array(&new);
         if let Some(item) = self.data.get_mut(id as usize) {
             *item = chars;
         }
+        Ok(())
     }
 }
 
@@ -0,0 +1,383 @@
+use crate::{
+    error::Error,
+    traits::{
+        build::ItemMod,
+        dictionary::{BuildIndexDictionary, IndexDictionary},
+        kind::Kind,
+    },
+};
+use compressed_vec::CVec;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Amount of terms within a bucket
+const BUCKET_SIZE: usize = 16;
+
+/// String dictionary storing the sorted terms front coded. Terms are grouped into buckets whose
+/// first term is stored in full. Every following term only stores the length of the prefix it
+/// shares with its predecessor and the remaining suffix
+#[derive(Serialize, Deserialize, Default)]
+pub struct FrontCodedDict {
+    /// Front coded terms in sort order
+    data: Vec<u8>,
+    /// Offsets of each bucket in `data`
+    buckets: CVec,
+    /// Maps sort positions to term IDs
+    rank_to_id: CVec,
+    /// Maps term IDs to sort positions
+    id_to_rank: CVec,
+}
+
+impl FrontCodedDict {
+    /// Returns the first term of a bucket
+    #[inline]
+    fn bucket_head(&self, bucket: usize) -> Option<&[u8]> {
+        let mut pos = self.buckets.get(bucket)? as usize;
+        let len = read_varint(&self.data, &mut pos)? as usize;
+        self.data.get(pos..pos.checked_add(len)?)
+    }
+
+    /// Returns a reader over the terms of a bucket or `None` if its offsets are invalid
+    #[inline]
+    fn bucket(&self, bucket: usize) -> Option<BucketReader> {
+        let start = self.buckets.get(bucket)? as usize;
+        let end = self
+            .buckets
+            .get(bucket + 1)
+            .map(|i| i as usize)
+            .unwrap_or(self.data.len());
+        Some(BucketReader::new(self.data.get(start..end)?))
+    }
+
+    /// Returns the sort position of the given term
+    fn rank_of(&self, term: &[u8]) -> Option<usize> {
+        // Last bucket whose head is not greater than `term`
+        let (mut left, mut right) = (0, self.buckets.len());
+        while left < right {
+            let mid = left + (right - left) / 2;
+            if self.bucket_head(mid)? <= term {
+                left = mid + 1;
+            } else {
+                right = mid;
+            }
+        }
+        let bucket = left.checked_sub(1)?;
+
+        let mut reader = self.bucket(bucket)?;
+        let mut pos = 0;
+        while let Some(bterm) = reader.next_term() {
+            match bterm.cmp(term) {
+                Ordering::Less => pos += 1,
+                Ordering::Equal => return Some(bucket * BUCKET_SIZE + pos),
+                Ordering::Greater => return None,
+            }
+        }
+
+        None
+    }
+
+    /// Returns the term at the given sort position
+    fn term_at(&self, rank: usize) -> Option<String> {
+        let mut reader = self.bucket(rank / BUCKET_SIZE)?;
+        for _ in 0..rank % BUCKET_SIZE {
+            reader.next_term()?;
+        }
+        let term = reader.next_term()?;
+        String::from_utf8(term.to_vec()).ok()
+    }
+}
+
+impl Kind for FrontCodedDict {
+    const KIND: &'static str = "dict.front_coded";
+}
+
+impl IndexDictionary<String> for FrontCodedDict {
+    #[inline]
+    fn get_id<F: Into<String>>(&self, term: F) -> Option<u32> {
+        let rank = self.rank_of(term.into().as_bytes())?;
+        self.rank_to_id.get(rank)
+    }
+
+    #[inline]
+    fn get_term(&self, id: u32) -> Option<String> {
+        let rank = self.id_to_rank.get(id as usize)?;
+        self.term_at(rank as usize)
+    }
+
+    #[inline]
+    fn has_term_id(&self, id: u32) -> bool {
+        (id as usize) < self.id_to_rank.len()
+    }
+
+    #[inline]
+    fn sort_pos(&self, pos: usize) -> Option<u32> {
+        self.rank_to_id.get(pos)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.id_to_rank.len()
+    }
+}
+
+/// Builder for `FrontCodedDict`. Collects all terms and encodes them at the end
+#[derive(Default)]
+pub struct FrontCodedDictBuilder {
+    terms: Vec<String>,
+}
+
+impl BuildIndexDictionary<String> for FrontCodedDictBuilder {
+    type Output = FrontCodedDict;
+
+    #[inline]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn insert(&mut self, i: String) -> Result<u32, Error> {
+        self.terms.push(i);
+        Ok(self.terms.len() as u32 - 1)
+    }
+
+    #[inline]
+    fn get(&self, id: u32) -> Option<String> {
+        self.terms.get(id as usize).cloned()
+    }
+
+    fn build(self) -> Self::Output {
+        let terms = self.terms;
+
+        let mut rank_to_id: Vec<u32> = (0..terms.len() as u32).collect();
+        rank_to_id.sort_by(|a, b| terms[*a as usize].cmp(&terms[*b as usize]));
+
+        let mut data = vec![];
+        let mut buckets = vec![];
+        let mut prev: &[u8] = &[];
+
+        for (rank, id) in rank_to_id.iter().enumerate() {
+            let term = terms[*id as usize].as_bytes();
+
+            if rank % BUCKET_SIZE == 0 {
+                buckets.push(data.len() as u32);
+                write_varint(&mut data, term.len() as u32);
+                data.extend_from_slice(term);
+            } else {
+                let shared = prev.iter().zip(term).take_while(|(a, b)| a == b).count();
+                write_varint(&mut data, shared as u32);
+                write_varint(&mut data, (term.len() - shared) as u32);
+                data.extend_from_slice(&term[shared..]);
+            }
+
+            prev = term;
+        }
+
+        let mut id_to_rank = vec![0; terms.len()];
+        for (rank, id) in rank_to_id.iter().enumerate() {
+            id_to_rank[*id as usize] = rank as u32;
+        }
+
+        FrontCodedDict {
+            data,
+            buckets: CVec::from(buckets),
+            rank_to_id: CVec::from(rank_to_id),
+            id_to_rank: CVec::from(id_to_rank),
+        }
+    }
+}
+
+impl ItemMod<String> for FrontCodedDictBuilder {
+    #[inline]
+    fn set_item(&mut self, id: u32, new: String) -> Result<(), Error> {
+        self.terms[id as usize] = new;
+        Ok(())
+    }
+}
+
+/// Decodes the terms of a single bucket
+struct BucketReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    term: Vec<u8>,
+}
+
+impl<'a> BucketReader<'a> {
+    #[inline]
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            term: vec![],
+        }
+    }
+
+    /// Decodes the next term of the bucket
+    fn next_term(&mut self) -> Option<&[u8]> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+
+        // The bucket head has no shared prefix
+        let shared = if self.pos == 0 {
+            0
+        } else {
+            read_varint(self.data, &mut self.pos)? as usize
+        };
+        let len = read_varint(self.data, &mut self.pos)? as usize;
+        let suffix = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+
+        self.term.truncate(shared);
+        self.term.extend_from_slice(suffix);
+        self.pos += len;
+
+        Some(&self.term)
+    }
+}
+
+/// Writes a LEB128 encoded integer
+#[inline]
+fn write_varint(out: &mut Vec<u8>, mut val: u32) {
+    while val >= 0x80 {
+        out.push((val as u8) | 0x80);
+        val >>= 7;
+    }
+    out.push(val as u8);
+}
+
+/// Reads a LEB128 encoded integer at `pos` and moves `pos` behind it. Returns `None` for
+/// truncated or overlong data
+#[inline]
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<u32> {
+    let mut val = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        val |= ((byte & 0x7F) as u32).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(val);
+        }
+        shift += 7;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{backend::memory::dict::default::Dictionary, traits::deser::DeSer};
+
+    /// Terms sharing long prefixes
+    fn vocabulary() -> Vec<String> {
+        let stems = ["inter", "internation", "konstruktion", "zusammen", "日本"];
+        let suffixes = [
+            "",
+            "al",
+            "ale",
+            "alisierung",
+            "en",
+            "es",
+            "ieren",
+            "ismus",
+            "lich",
+        ];
+        let mut out = vec![];
+        for stem in stems {
+            for suffix in suffixes {
+                for n in 0..20 {
+                    out.push(format!("{stem}{suffix}{n}"));
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    pub fn test_front_coded_dict() {
+        let terms = vocabulary();
+
+        let mut builder = FrontCodedDictBuilder::new();
+        let ids: Vec<_> = terms
+            .iter()
+            .map(|i| builder.insert(i.clone()).unwrap())
+            .collect();
+        let dict = FrontCodedDict::decode_vec(&builder.build().encode_vec().unwrap()).unwrap();
+
+        assert_eq!(dict.len(), terms.len());
+        for (term, id) in terms.iter().zip(ids) {
+            assert_eq!(dict.get_id(term.as_str()), Some(id));
+            assert_eq!(dict.get_term(id).as_ref(), Some(term));
+        }
+
+        assert_eq!(dict.get_id(""), None);
+        assert_eq!(dict.get_id("inter"), None);
+        assert_eq!(dict.get_id("zzz"), None);
+        assert_eq!(dict.get_term(terms.len() as u32), None);
+
+        let mut sorted = terms.clone();
+        sorted.sort_unstable();
+        let iter: Vec<_> = dict.range::<std::ops::RangeFull>(..).map(|i| i.1).collect();
+        assert_eq!(iter, sorted);
+
+        let empty = FrontCodedDictBuilder::new().build();
+        assert_eq!(empty.get_id("a"), None);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    pub fn test_varint() {
+        let mut data = vec![];
+        for val in [0, 127, 128, 300, u32::MAX] {
+            write_varint(&mut data, val);
+        }
+        let mut pos = 0;
+        for val in [0, 127, 128, 300, u32::MAX] {
+            assert_eq!(read_varint(&data, &mut pos), Some(val));
+        }
+        assert_eq!(read_varint(&data, &mut pos), None);
+
+        // Continuation bits without end
+        assert_eq!(read_varint(&[0x80, 0x80], &mut 0), None);
+        assert_eq!(read_varint(&[0xFF; 6], &mut 0), None);
+    }
+
+    #[test]
+    pub fn test_corrupt_buckets() {
+        let terms = vocabulary();
+        let mut builder = FrontCodedDictBuilder::new();
+        for term in &terms {
+            builder.insert(term.clone()).unwrap();
+        }
+        let mut dict = builder.build();
+        let in_bucket = |dict: &FrontCodedDict, n: usize| dict.sort_pos(n * BUCKET_SIZE).unwrap();
+
+        // Bucket starting after the next one
+        let next = dict.buckets.get(2).unwrap();
+        dict.buckets.set(1, next + 1);
+        assert_eq!(dict.get_term(in_bucket(&dict, 1)), None);
+
+        // Bucket starting after the end of the data
+        let last = dict.buckets.len() - 1;
+        dict.buckets.set(last, dict.data.len() as u32 + 1);
+        assert_eq!(dict.get_term(in_bucket(&dict, last)), None);
+        assert_eq!(dict.get_id(terms.iter().max().unwrap().as_str()), None);
+    }
+
+    #[test]
+    pub fn test_size() {
+        let terms = vocabulary();
+
+        let mut builder = FrontCodedDictBuilder::new();
+        let mut mem_file = Dictionary::<String>::new();
+        {
+            let mut push = mem_file.multi_push();
+            for term in &terms {
+                builder.insert(term.clone()).unwrap();
+                push.insert(term.clone()).unwrap();
+            }
+        }
+
+        let front_coded = builder.build().encode_vec().unwrap().len();
+        let mem_file = mem_file.encode_vec().unwrap().len();
+        assert!(front_coded < mem_file);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    error::Error,
+    traits::{
+        build::ItemMod,
+        dictionary::{BuildIndexDictionary, IndexDictionary},
+        kind::Kind,
+    },
+};
+use ::fst::{map::Stream, Automaton, IntoStreamer, Map, Streamer};
+use compressed_vec::CVec;
+use serde::{Deserialize, Serialize};
+
+/// String dictionary backed by a finite state transducer mapping each term to its position in
+/// the sorted term list. Terms are stored prefix and suffix compressed and lookups don't have to
+/// decode any term
+#[derive(Serialize, Deserialize)]
+pub struct FstDict {
+    #[serde(with = "fst_map")]
+    map: Map<Vec<u8>>,
+    /// Maps sort positions to term IDs
+    rank_to_id: CVec,
+    /// Maps term IDs to sort positions
+    id_to_rank: CVec,
+}
+
+impl FstDict {
+    /// Returns an iterator over all terms in lexicographic order yielding their IDs and values
+    #[inline]
+    pub fn stream(&self) -> FstStream<'_, ::fst::automaton::AlwaysMatch> {
+        FstStream::new(self.map.stream(), &self.rank_to_id)
+    }
+
+    /// Returns an iterator over all terms accepted by the given automaton in lexicographic order
+    #[inline]
+    pub fn search<A: Automaton>(&self, aut: A) -> FstStream<'_, A> {
+        FstStream::new(self.map.search(aut).into_stream(), &self.rank_to_id)
+    }
+
+    /// Returns the underlying transducer
+    #[inline]
+    pub fn fst(&self) -> &Map<Vec<u8>> {
+        &self.map
+    }
+
+    /// Returns the term at the given sort position by walking the transducer. Since the outputs
+    /// are ascending sort positions, each transition carries the smallest position reachable
+    /// through it
+    fn term_at(&self, rank: u64) -> Option<String> {
+        let fst = self.map.as_fst();
+        let mut node = fst.root();
+        let mut acc = 0;
+        let mut out = vec![];
+
+        loop {
+            if node.is_final() && acc + node.final_output().value() == rank {
+                return String::from_utf8(out).ok();
+            }
+
+            let trans = node
+                .transitions()
+                .take_while(|t| acc + t.out.value() <= rank)
+                .last()?;
+            acc += trans.out.value();
+            out.push(trans.inp);
+            node = fst.node(trans.addr);
+        }
+    }
+}
+
+impl Kind for FstDict {
+    const KIND: &'static str = "dict.fst";
+}
+
+impl IndexDictionary<String> for FstDict {
+    #[inline]
+    fn get_id<F: Into<String>>(&self, term: F) -> Option<u32> {
+        let rank = self.map.get(term.into())?;
+        self.rank_to_id.get(rank as usize)
+    }
+
+    #[inline]
+    fn get_term(&self, id: u32) -> Option<String> {
+        let rank = self.id_to_rank.get(id as usize)?;
+        self.term_at(rank as u64)
+    }
+
+    #[inline]
+    fn has_term_id(&self, id: u32) -> bool {
+        (id as usize) < self.id_to_rank.len()
+    }
+
+    #[inline]
+    fn sort_pos(&self, pos: usize) -> Option<u32> {
+        self.rank_to_id.get(pos)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.id_to_rank.len()
+    }
+}
+
+/// Builder for `FstDict`. Collects all terms and builds the transducer at the end
+#[derive(Default)]
+pub struct FstDictBuilder {
+    terms: Vec<String>,
+}
+
+impl BuildIndexDictionary<String> for FstDictBuilder {
+    type Output = FstDict;
+
+    #[inline]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn insert(&mut self, i: String) -> Result<u32, Error> {
+        self.terms.push(i);
+        Ok(self.terms.len() as u32 - 1)
+    }
+
+    #[inline]
+    fn get(&self, id: u32) -> Option<String> {
+        self.terms.get(id as usize).cloned()
+    }
+
+    /// Builds the dictionary. Panics if a term was inserted multiple times
+    fn build(self) -> Self::Output {
+        let terms = self.terms;
+
+        let mut rank_to_id: Vec<u32> = (0..terms.len() as u32).collect();
+        rank_to_id.sort_by(|a, b| terms[*a as usize].cmp(&terms[*b as usize]));
+
+        let sorted = rank_to_id
+            .iter()
+            .enumerate()
+            .map(|(rank, id)| (&terms[*id as usize], rank as u64));
+        let map = Map::from_iter(sorted).expect("Terms must be unique");
+
+        let mut id_to_rank = vec![0; terms.len()];
+        for (rank, id) in rank_to_id.iter().enumerate() {
+            id_to_rank[*id as usize] = rank as u32;
+        }
+
+        FstDict {
+            map,
+            rank_to_id: CVec::from(rank_to_id),
+            id_to_rank: CVec::from(id_to_rank),
+        }
+    }
+}
+
+impl ItemMod<String> for FstDictBuilder {
+    #[inline]
+    fn set_item(&mut self, id: u32, new: String) -> Result<(), Error> {
+        self.terms[id as usize] = new;
+        Ok(())
+    }
+}
+
+/// Iterator over a stream of terms of an `FstDict` yielding their IDs and values
+pub struct FstStream<'a, A: Automaton> {
+    inner: Stream<'a, A>,
+    rank_to_id: &'a CVec,
+}
+
+impl<'a, A: Automaton> FstStream<'a, A> {
+    #[inline]
+    fn new(inner: Stream<'a, A>, rank_to_id: &'a CVec) -> Self {
+        Self { inner, rank_to_id }
+    }
+}
+
+impl<'a, A: Automaton> Iterator for FstStream<'a, A> {
+    type Item = (u32, String);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (term, rank) = self.inner.next()?;
+        let id = self.rank_to_id.get(rank as usize)?;
+        Some((id, String::from_utf8_lossy(term).into_owned()))
+    }
+}
+
+/// (De)serializes a transducer as its raw bytes
+mod fst_map {
+    use ::fst::Map;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    #[inline]
+    pub fn serialize<S: Serializer>(map: &Map<Vec<u8>>, ser: S) -> Result<S::Ok, S::Error> {
+        map.as_fst().as_bytes().serialize(ser)
+    }
+
+    #[inline]
+    pub fn deserialize<'de, D: Deserializer<'de>>(deser: D) -> Result<Map<Vec<u8>>, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deser)?;
+        Map::new(bytes).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::traits::deser::DeSer;
+    use ::fst::automaton::Str;
+
+    #[test]
+    pub fn test_fst_dict() {
+        let inpdict = ["think", "aal", "auch", "zoo", "make", "", "日本語"];
+
+        let mut builder = FstDictBuilder::new();
+        let ids: Vec<_> = inpdict
+            .iter()
+            .map(|i| builder.insert(i.to_string()).unwrap())
+            .collect();
+        let dict = FstDict::decode_vec(&builder.build().encode_vec().unwrap()).unwrap();
+
+        assert_eq!(dict.len(), inpdict.len());
+        for (term, id) in inpdict.iter().zip(ids) {
+            assert_eq!(dict.get_id(*term), Some(id));
+            assert_eq!(dict.get_term(id).as_deref(), Some(*term));
+        }
+        assert_eq!(dict.get_id("auto"), None);
+        assert_eq!(dict.get_term(inpdict.len() as u32), None);
+
+        let sorted: Vec<_> = dict.stream().map(|i| i.1).collect();
+        let mut exp = inpdict.to_vec();
+        exp.sort_unstable();
+        assert_eq!(sorted, exp);
+
+        let prefix: Vec<_> = dict.search(Str::new("a").starts_with()).collect();
+        assert_eq!(
+            prefix,
+            vec![(1, "aal".to_string()), (2, "auch".to_string())]
+        );
+        assert_eq!(dict.terms_with_prefix("a").collect::<Vec<_>>(), prefix);
+    }
+}
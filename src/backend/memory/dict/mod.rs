@@ -1,2 +1,4 @@
 pub mod default;
 pub mod fixed_len;
+pub mod front_coded;
+pub mod fst;
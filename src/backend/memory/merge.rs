@@ -0,0 +1,178 @@
+use super::MemBackend;
+use crate::{
+    error::Error,
+    stats::IndexStats,
+    traits::{
+        backend::{Backend, NewBackend},
+        deser::DeSer,
+        dict_item::DictItem,
+        dictionary::{BuildIndexDictionary, IndexDictionary},
+        postings::{BuildPostings, IndexPostings},
+        storage::{BuildIndexStorage, IndexStorage},
+    },
+    Index,
+};
+use std::{cmp::Ordering, collections::HashMap, ops::RangeFull};
+
+impl<T, S, Dic, Stor, Post> Index<MemBackend<T, S, Dic, Stor, Post>, T, S>
+where
+    Dic: IndexDictionary<T> + DeSer,
+    Stor: IndexStorage<S> + DeSer,
+    Post: IndexPostings + DeSer,
+    T: DictItem,
+    S: DeSer,
+{
+    /// Merges two indexes into a new one without re-tokenizing their items. `DD`, `SS` and `PP`
+    /// are the builders of the indexes dictionary, storage and postings.
+    ///
+    /// Terms and items of `self` keep their IDs. Terms only in `other` and all items of `other`
+    /// get appended. Postings get concatenated list by list for each postings slot, so sorted
+    /// postings stay sorted. Deleted items are dropped. Term positions are kept if the postings
+    /// store them.
+    ///
+    /// Returns an error if a term or item can't be decoded or encoded again or if the
+    /// dictionaries were built with different settings
+    pub fn merge<DD, SS, PP>(&self, other: &Self) -> Result<Self, Error>
+    where
+        DD: BuildIndexDictionary<T, Output = Dic>,
+        SS: BuildIndexStorage<S, Output = Stor>,
+        PP: BuildPostings<Output = Post, PostingList = Vec<u32>>,
+    {
+        let mut dict = DD::for_merge(self.dict(), other.dict())?;
+        let mut a_terms = Vec::with_capacity(self.dict().len());
+        for id in 0..self.dict().len() as u32 {
+            a_terms.push(dict.insert(get_term(self.dict(), id)?)?);
+        }
+        let b_terms = merge_terms(self.dict(), other.dict(), &a_terms, &mut dict)?;
+        dict.finish();
+
+        let mut storage = SS::new();
+        let a_items = merge_items(&**self, &mut storage)?;
+        let b_items = merge_items(&**other, &mut storage)?;
+
+        let slots = self.posting_count().max(other.posting_count());
+        let mut stats = IndexStats::with_postings_len(slots);
+        let postings = (0..slots as u32)
+            .map(|p_id| {
+                let mut map: HashMap<u32, Vec<u32>> = HashMap::new();
+                let mut positions: HashMap<u32, HashMap<u32, Vec<u32>>> = HashMap::new();
+                let sources = [
+                    (&**self, &a_terms, &a_items),
+                    (&**other, &b_terms, &b_items),
+                ];
+
+                for (backend, term_map, item_map) in sources {
+                    let postings = match backend.postings(p_id) {
+                        Some(p) => p,
+                        None => continue,
+                    };
+                    // Lengths are taken over from the source statistics, as postings may contain
+                    // an item only once or along with its term frequency
+                    let src_stats = backend.stats().and_then(|i| i.posting(p_id));
+                    let stats = stats.posting_mut(p_id).unwrap();
+                    if let Some(src_stats) = src_stats {
+                        let items = item_map.iter().enumerate();
+                        for (item, new_item) in items.filter_map(|(i, n)| Some((i, (*n)?))) {
+                            stats.add(new_item, src_stats.item_len(item as u32));
+                        }
+                    }
+
+                    for (t_id, new_t_id) in term_map.iter().enumerate() {
+                        let items = postings
+                            .term_positions(t_id as u32)
+                            .unwrap_or_else(|| {
+                                postings.cursor(t_id as u32).map(|i| (i, vec![])).collect()
+                            })
+                            .into_iter()
+                            .filter_map(|(i, pos)| Some((item_map[i as usize]?, pos)));
+                        let list = map.entry(*new_t_id).or_default();
+                        for (item, pos) in items {
+                            list.push(item);
+                            if src_stats.is_none() {
+                                stats.add(item, 1);
+                            }
+                            if !pos.is_empty() {
+                                positions.entry(*new_t_id).or_default().insert(item, pos);
+                            }
+                        }
+                    }
+                }
+
+                map.retain(|_, list| !list.is_empty());
+                let mut postings = PP::from_map(map);
+                if !positions.is_empty() {
+                    postings.set_positions(positions);
+                }
+                postings.build()
+            })
+            .collect();
+
+        let mut backend = MemBackend::new(dict.build(), postings, storage.build());
+        backend.set_stats(stats);
+        Ok(Index::new(backend))
+    }
+}
+
+/// Returns the term with the given ID, which has to exist in the dictionary
+fn get_term<T: DictItem, D: IndexDictionary<T>>(dict: &D, id: u32) -> Result<T, Error> {
+    dict.get_term(id)
+        .ok_or_else(|| Error::Corrupt(format!("term {id} can't be read")))
+}
+
+/// Inserts all terms of `b` which are not in `a` into `dict` and returns the new IDs of all
+/// terms of `b`. Equal terms are found by walking both dictionaries in sort order
+fn merge_terms<T, D, DD>(a: &D, b: &D, a_terms: &[u32], dict: &mut DD) -> Result<Vec<u32>, Error>
+where
+    T: DictItem,
+    D: IndexDictionary<T>,
+    DD: BuildIndexDictionary<T>,
+{
+    let mut b_terms: Vec<Option<u32>> = vec![None; b.len()];
+
+    let mut a_iter = a.range::<RangeFull>(..).peekable();
+    for (b_id, b_term) in b.range::<RangeFull>(..) {
+        while let Some((a_id, a_term)) = a_iter.peek() {
+            match a_term.cmp(&b_term) {
+                Ordering::Less => {
+                    a_iter.next();
+                }
+                Ordering::Equal => {
+                    b_terms[b_id as usize] = Some(a_terms[*a_id as usize]);
+                    break;
+                }
+                Ordering::Greater => break,
+            }
+        }
+    }
+
+    b_terms
+        .into_iter()
+        .enumerate()
+        .map(|(id, new)| match new {
+            Some(new) => Ok(new),
+            None => dict.insert(get_term(b, id as u32)?),
+        })
+        .collect()
+}
+
+/// Inserts all items of `backend` that aren't deleted into `storage` and returns their new IDs
+fn merge_items<B, T, S, SS>(backend: &B, storage: &mut SS) -> Result<Vec<Option<u32>>, Error>
+where
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+    SS: BuildIndexStorage<S>,
+{
+    (0..backend.storage().len() as u32)
+        .map(|id| {
+            if backend.is_deleted(id) {
+                return Ok(None);
+            }
+            let item = backend
+                .storage()
+                .get_item(id)
+                .ok_or_else(|| Error::Corrupt(format!("item {id} can't be read")))?;
+            storage.insert(item).map(Some)
+        })
+        .collect()
+}
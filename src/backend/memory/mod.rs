@@ -1,20 +1,33 @@
 pub mod backend;
 pub mod build;
 pub mod dict;
+mod merge;
 pub mod postings;
 pub mod presets;
 pub mod storage;
 
-use crate::traits::{
-    backend::{Backend, BeStorageMut, NewBackend},
-    deser::DeSer,
-    dict_item::DictItem,
-    dictionary::IndexDictionary,
-    postings::IndexPostings,
-    storage::IndexStorage,
+use crate::{
+    error::Error,
+    stats::IndexStats,
+    traits::{
+        backend::{Backend, BackendMut, BeStorageMut, NewBackend},
+        deser::DeSer,
+        dict_item::DictItem,
+        dictionary::{IndexDictionary, IndexDictionaryMod},
+        postings::{IndexPostings, PostingsMut},
+        storage::{IndexStorage, IndexStorageMod},
+    },
 };
+use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
-use std::{io::Read, marker::PhantomData};
+use std::{
+    io::{Read, Write},
+    marker::PhantomData,
+};
+
+/// Version of the encoded in-memory index format. Encoded indexes of other versions can't be
+/// decoded
+pub const FORMAT_VERSION: u32 = 2;
 
 /// Generic in-memory index backend to build any kinds of indexes
 #[derive(Serialize, Deserialize, Default)]
@@ -22,6 +35,9 @@ pub struct MemBackend<T, S, Dic, Stor, Post> {
     dict: Dic,
     postings_list: Vec<Post>,
     storage: Stor,
+    stats: IndexStats,
+    /// Tombstones of deleted items
+    deleted: RoaringBitmap,
     p: PhantomData<T>,
     p2: PhantomData<S>,
 }
@@ -38,16 +54,28 @@ where
     type Storage = Stor;
     type Postings = Post;
 
-    fn decode_from<R: Read>(reader: R) -> Option<Self>
+    fn decode_from<R: Read>(mut reader: R) -> Result<Self, Error>
     where
         Self: Sized,
     {
-        bincode::deserialize_from(reader).ok()
+        let found: u32 = bincode::deserialize_from(&mut reader)?;
+        if found != FORMAT_VERSION {
+            return Err(Error::VersionMismatch {
+                expected: FORMAT_VERSION,
+                found,
+            });
+        }
+        Ok(bincode::deserialize_from(reader)?)
+    }
+
+    #[inline]
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serialize(&(FORMAT_VERSION, self))?)
     }
 
     #[inline]
-    fn encode(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+    fn encode_into<W: Write>(&self, out: W) -> Result<(), Error> {
+        Ok(bincode::serialize_into(out, &(FORMAT_VERSION, self))?)
     }
 
     #[inline]
@@ -68,6 +96,16 @@ where
     fn storage(&self) -> &Self::Storage {
         &self.storage
     }
+
+    #[inline]
+    fn stats(&self) -> Option<&IndexStats> {
+        Some(&self.stats)
+    }
+
+    #[inline]
+    fn is_deleted(&self, id: u32) -> bool {
+        self.deleted.contains(id)
+    }
 }
 
 impl<T, S, Dic, Stor, Post> BeStorageMut<T, S> for MemBackend<T, S, Dic, Stor, Post>
@@ -84,6 +122,76 @@ where
     }
 }
 
+impl<T, S, Dic, Stor, Post> BackendMut<T, S> for MemBackend<T, S, Dic, Stor, Post>
+where
+    Dic: IndexDictionaryMod<T> + DeSer,
+    Stor: IndexStorageMod<S> + DeSer,
+    Post: PostingsMut + DeSer,
+    T: DictItem,
+    S: DeSer,
+{
+    fn add_item(&mut self, postings_id: u32, item: S, terms: Vec<T>) -> Result<u32, Error> {
+        if postings_id as usize >= self.postings_list.len() {
+            panic!(
+                "Invalid postings index {} of {}",
+                postings_id,
+                self.postings_list.len(),
+            );
+        }
+
+        // Terms are inserted first, so an item that can't be encoded only leaves unused terms
+        let term_ids = self.dict.insert_terms(terms)?;
+        let item_id = self.storage.push_item(item)?;
+        let term_count = term_ids.len() as u32;
+
+        // The order of the terms are their positions. Each list gets the item once, as with
+        // `BuildOption::UniquePostings`
+        let mut terms: Vec<(u32, u32)> = term_ids.into_iter().zip(0..).collect();
+        terms.sort_unstable();
+
+        let postings = &mut self.postings_list[postings_id as usize];
+        for term in terms.chunk_by(|a, b| a.0 == b.0) {
+            let positions: Vec<u32> = term.iter().map(|i| i.1).collect();
+            postings.add_item_positioned(term[0].0, item_id, &positions);
+        }
+
+        if let Some(stats) = self.stats.posting_mut(postings_id) {
+            stats.add(item_id, term_count);
+        }
+
+        Ok(item_id)
+    }
+
+    fn delete_item(&mut self, id: u32) -> bool {
+        if !self.storage.has_item(id) || !self.deleted.insert(id) {
+            return false;
+        }
+
+        self.stats.remove(id);
+        true
+    }
+
+    #[inline]
+    fn deleted_count(&self) -> usize {
+        self.deleted.len() as usize
+    }
+
+    fn compact(&mut self) -> usize {
+        // Postings appending copies on update are rewritten even without deleted items
+        let deleted = std::mem::take(&mut self.deleted);
+        // Items move up by the amount of deleted items in front of them
+        let new_id = |id: u32| (!deleted.contains(id)).then(|| id - deleted.rank(id) as u32);
+
+        for postings in &mut self.postings_list {
+            postings.remap_items(new_id);
+        }
+        self.storage.retain_items(|id| !deleted.contains(id));
+        self.stats.retain_items(|id| !deleted.contains(id));
+
+        deleted.len() as usize
+    }
+}
+
 impl<T, S, Dic, Stor, Post> NewBackend<T, S> for MemBackend<T, S, Dic, Stor, Post>
 where
     Dic: IndexDictionary<T> + DeSer,
@@ -98,8 +206,15 @@ where
             dict,
             postings_list,
             storage,
+            stats: IndexStats::default(),
+            deleted: RoaringBitmap::new(),
             p: PhantomData,
             p2: PhantomData,
         }
     }
+
+    #[inline]
+    fn set_stats(&mut self, stats: IndexStats) {
+        self.stats = stats;
+    }
 }
@@ -0,0 +1,156 @@
+use crate::traits::{
+    kind::Kind,
+    postings::{BuildPostings, IndexPostings, PostingCursor, PostingsMut},
+};
+use roaring::{bitmap::Iter, RoaringBitmap};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Postings storing each list as roaring bitmap. Efficient for dense terms mapping to many items
+/// and allows fast set operations between lists. Lists are sorted and contain each item once.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Postings {
+    index: HashMap<u32, RoaringBitmap>,
+}
+
+impl Postings {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            index: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn from_map(map: HashMap<u32, Vec<u32>>) -> Self {
+        let index = map
+            .into_iter()
+            .map(|(k, v)| (k, v.into_iter().collect()))
+            .collect();
+        Self { index }
+    }
+
+    /// Returns the bitmap of the postings-list with the given ID
+    #[inline]
+    pub fn get_bitmap(&self, id: u32) -> Option<&RoaringBitmap> {
+        self.index.get(&id)
+    }
+
+    /// Returns all items contained in all of the given lists
+    pub fn intersect(&self, ids: &[u32]) -> RoaringBitmap {
+        let mut bitmaps: Vec<_> = match ids.iter().map(|i| self.get_bitmap(*i)).collect() {
+            Some(b) => b,
+            None => return RoaringBitmap::new(),
+        };
+        bitmaps.sort_by_key(|i| i.len());
+
+        let mut iter = bitmaps.into_iter();
+        let first = iter.next().cloned().unwrap_or_default();
+        iter.fold(first, |acc, i| acc & i)
+    }
+
+    /// Returns all items contained in any of the given lists
+    pub fn union(&self, ids: &[u32]) -> RoaringBitmap {
+        ids.iter()
+            .filter_map(|i| self.get_bitmap(*i))
+            .fold(RoaringBitmap::new(), |acc, i| acc | i)
+    }
+}
+
+impl Kind for Postings {
+    const KIND: &'static str = "postings.bitmap";
+}
+
+impl IndexPostings for Postings {
+    type Cursor<'a> = Cursor<'a>;
+
+    #[inline]
+    fn cursor(&self, id: u32) -> Self::Cursor<'_> {
+        Cursor {
+            iter: self.get_bitmap(id).map(|i| i.iter()),
+        }
+    }
+
+    #[inline]
+    fn get_posting(&self, id: u32) -> Vec<u32> {
+        self.get_bitmap(id)
+            .map(|i| i.iter().collect())
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn has_id(&self, id: u32) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    #[inline]
+    fn posting_size(&self, id: u32) -> usize {
+        self.get_bitmap(id).map(|i| i.len() as usize).unwrap_or(0)
+    }
+
+    /// Returns the highest term ID + 1, like other postings that allow indexing all IDs below
+    #[inline]
+    fn len(&self) -> usize {
+        self.index
+            .keys()
+            .max()
+            .map(|i| *i as usize + 1)
+            .unwrap_or(0)
+    }
+}
+
+impl PostingsMut for Postings {
+    #[inline]
+    fn add_item(&mut self, id: u32, item: u32) {
+        self.index.entry(id).or_default().insert(item);
+    }
+
+    fn remap_items<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> Option<u32>,
+    {
+        self.index.retain(|_, list| {
+            *list = list.iter().filter_map(&mut f).collect();
+            !list.is_empty()
+        });
+    }
+}
+
+impl BuildPostings for Postings {
+    type Output = Self;
+    type PostingList = Vec<u32>;
+
+    #[inline]
+    fn from_map(map: HashMap<u32, Self::PostingList>) -> Self {
+        Self::from_map(map)
+    }
+
+    #[inline]
+    fn build(self) -> Self::Output {
+        self
+    }
+}
+
+/// Cursor over a bitmap postings-list
+pub struct Cursor<'a> {
+    iter: Option<Iter<'a>>,
+}
+
+impl<'a> Iterator for Cursor<'a> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.as_mut()?.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            Some(i) => i.size_hint(),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<'a> PostingCursor for Cursor<'a> {}
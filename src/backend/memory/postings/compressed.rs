@@ -1,4 +1,7 @@
-use crate::traits::postings::{BuildPostings, IndexPostings};
+use crate::traits::{
+    kind::Kind,
+    postings::{BuildPostings, IndexPostings, PostingCursor, PostingsMut},
+};
 use compressed_vec::{buffered::BufCVecRef, CVec};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -10,6 +13,9 @@ pub struct Postings {
     index: CVec,
     /// Contains the vector ids for each dimension
     data: CVec,
+    /// Words in `data` belonging to lists that were replaced by `add_item`
+    #[serde(skip)]
+    dead: usize,
 }
 
 impl Postings {
@@ -18,6 +24,7 @@ impl Postings {
         Self {
             index: CVec::new(),
             data: CVec::new(),
+            dead: 0,
         }
     }
 
@@ -28,9 +35,18 @@ impl Postings {
 
         let mut prev_id: Option<u32> = None;
 
+        // Unmapped dimensions point to a single empty list
+        let mut empty = None;
+        let mut empty_pos = |data: &mut CVec| {
+            *empty.get_or_insert_with(|| {
+                data.push(0);
+                data.len() as u32 - 1
+            })
+        };
+
         let first = *map.iter().map(|i| i.0).min().unwrap_or(&0);
         for _ in 0..first {
-            index.push(0);
+            index.push(empty_pos(&mut data));
         }
 
         for (term_id, item_ids) in map.into_iter().sorted_by(|a, b| a.0.cmp(&b.0)) {
@@ -38,11 +54,10 @@ impl Postings {
                 prev_id = Some(term_id);
             }
 
-            // Fill non mapped dimensions with 0s to make the CVS replace a HashMap
+            // Fill non mapped dimensions to make the CVec replace a HashMap
             let ld = prev_id.as_ref().unwrap();
             for _ in ld + 1..term_id {
-                index.push(data.len() as u32);
-                //data.push(0);
+                index.push(empty_pos(&mut data));
             }
 
             // Push index indice
@@ -55,7 +70,11 @@ impl Postings {
             prev_id = Some(term_id);
         }
 
-        Self { index, data }
+        Self {
+            index,
+            data,
+            dead: 0,
+        }
     }
 
     #[inline]
@@ -83,12 +102,35 @@ impl Postings {
     }
 }
 
+impl Kind for Postings {
+    const KIND: &'static str = "postings.compressed";
+}
+
 impl IndexPostings for Postings {
+    type Cursor<'a> = Cursor<'a>;
+
     #[inline]
     fn get_posting(&self, id: u32) -> Vec<u32> {
         self.get(id).unwrap_or_default()
     }
 
+    #[inline]
+    fn cursor(&self, id: u32) -> Self::Cursor<'_> {
+        let mut buf = BufCVecRef::new(&self.data);
+
+        let (pos, end) = self
+            .index
+            .get(id as usize)
+            .and_then(|start| {
+                let start = start as usize;
+                let len = *buf.get_buffered(start)? as usize;
+                Some((start + 1, start + 1 + len))
+            })
+            .unwrap_or((0, 0));
+
+        Cursor { buf, pos, end }
+    }
+
     #[inline]
     fn has_id(&self, id: u32) -> bool {
         self.get(id).is_some()
@@ -104,6 +146,53 @@ impl IndexPostings for Postings {
     }
 }
 
+impl PostingsMut for Postings {
+    /// Appends a copy of the list including `item` to the data. The space of the old list gets
+    /// reclaimed by `remap_items`, which runs once half of the data is dead
+    fn add_item(&mut self, id: u32, item: u32) {
+        let mut items = self.get(id).unwrap_or_default();
+        if !items.is_empty() {
+            self.dead += items.len() + 1;
+        }
+        items.push(item);
+
+        if (self.index.len() as u32) < id {
+            let empty = self.data.len() as u32;
+            self.data.push(0);
+            while (self.index.len() as u32) < id {
+                self.index.push(empty);
+            }
+        }
+
+        let start = self.data.len() as u32;
+        self.data.push(items.len() as u32);
+        self.data.extend(items);
+
+        if self.index.len() as u32 == id {
+            self.index.push(start);
+        } else {
+            self.index.set(id as usize, start);
+        }
+
+        if self.dead > self.data.len() / 2 {
+            self.remap_items(Some);
+        }
+    }
+
+    fn remap_items<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> Option<u32>,
+    {
+        let map = (0..self.index.len() as u32)
+            .filter_map(|id| {
+                let items: Vec<_> = self.get(id)?.into_iter().filter_map(&mut f).collect();
+                (!items.is_empty()).then_some((id, items))
+            })
+            .collect();
+        *self = Self::from_map(map);
+    }
+}
+
 impl BuildPostings for Postings {
     type Output = Self;
     type PostingList = Vec<u32>;
@@ -118,3 +207,32 @@ impl BuildPostings for Postings {
         self
     }
 }
+
+/// Cursor over a compressed postings-list
+pub struct Cursor<'a> {
+    buf: BufCVecRef<'a>,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for Cursor<'a> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let item = *self.buf.get_buffered(self.pos)?;
+        self.pos += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.pos;
+        (len, Some(len))
+    }
+}
+
+impl<'a> PostingCursor for Cursor<'a> {}
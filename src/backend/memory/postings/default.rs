@@ -1,4 +1,7 @@
-use crate::traits::postings::{BuildPostings, IndexPostings};
+use crate::traits::{
+    kind::Kind,
+    postings::{BuildPostings, IndexPostings, PostingsMut, SliceCursor},
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -26,12 +29,23 @@ impl Postings {
     }
 }
 
+impl Kind for Postings {
+    const KIND: &'static str = "postings.default";
+}
+
 impl IndexPostings for Postings {
+    type Cursor<'a> = SliceCursor<'a>;
+
     #[inline]
     fn get_posting(&self, id: u32) -> Vec<u32> {
         self.get(id).cloned().unwrap_or_default()
     }
 
+    #[inline]
+    fn cursor(&self, id: u32) -> Self::Cursor<'_> {
+        SliceCursor::new(self.get(id).map(|i| i.as_slice()).unwrap_or_default())
+    }
+
     #[inline]
     fn has_id(&self, id: u32) -> bool {
         self.get(id).is_some()
@@ -47,6 +61,23 @@ impl IndexPostings for Postings {
     }
 }
 
+impl PostingsMut for Postings {
+    #[inline]
+    fn add_item(&mut self, id: u32, item: u32) {
+        self.index.entry(id).or_default().push(item);
+    }
+
+    fn remap_items<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> Option<u32>,
+    {
+        self.index.retain(|_, list| {
+            *list = list.iter().filter_map(|i| f(*i)).collect();
+            !list.is_empty()
+        });
+    }
+}
+
 impl BuildPostings for Postings {
     type Output = Self;
     type PostingList = Vec<u32>;
@@ -1,2 +1,6 @@
+pub mod bitmap;
 pub mod compressed;
 pub mod default;
+pub mod packed;
+pub mod positional;
+pub mod tf;
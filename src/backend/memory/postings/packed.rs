@@ -0,0 +1,323 @@
+use crate::traits::{
+    kind::Kind,
+    postings::{BuildPostings, IndexPostings, PostingCursor},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Amount of items in a full block
+pub const BLOCK_SIZE: usize = 128;
+
+/// Postings storing sorted lists delta encoded in bit packed blocks. Each block keeps its first
+/// and last item as skip data, which allows seeking without decoding previous blocks.
+/// Posting lists get sorted when building.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Postings {
+    /// Lists indexed by their term ID
+    lists: Vec<List>,
+    blocks: Vec<Block>,
+    /// Bit packed deltas of all blocks
+    data: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+struct List {
+    len: u32,
+    first_block: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct Block {
+    /// First item in the block. Deltas are relative to it
+    first: u32,
+    /// Last item in the block
+    last: u32,
+    /// Bit width of the deltas
+    bits: u8,
+    /// Position of the blocks first word in `data`
+    offset: u32,
+}
+
+impl Postings {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_map(map: HashMap<u32, Vec<u32>>) -> Self {
+        let mut out = Self::new();
+
+        let mut map: Vec<_> = map.into_iter().collect();
+        map.sort_unstable_by_key(|i| i.0);
+
+        for (term_id, mut item_ids) in map {
+            if item_ids.is_empty() {
+                continue;
+            }
+            item_ids.sort_unstable();
+
+            out.lists.resize(term_id as usize + 1, List::default());
+            out.lists[term_id as usize] = List {
+                len: item_ids.len() as u32,
+                first_block: out.blocks.len() as u32,
+            };
+
+            for chunk in item_ids.chunks(BLOCK_SIZE) {
+                out.push_block(chunk);
+            }
+        }
+
+        out
+    }
+
+    /// Returns the first item of the list `id` that is `>= target`
+    pub fn first_geq(&self, id: u32, target: u32) -> Option<u32> {
+        let blocks = self.list_blocks(id)?;
+        let block_pos = blocks.partition_point(|b| b.last < target);
+        let block = blocks.get(block_pos)?;
+
+        let mut buf = [0u32; BLOCK_SIZE];
+        let len = self.block_len(id, block_pos);
+        self.decode_block(block, len, &mut buf)?;
+        buf[..len].iter().find(|i| **i >= target).copied()
+    }
+
+    /// Returns `true` if the list `id` contains the given item
+    #[inline]
+    pub fn contains(&self, id: u32, item: u32) -> bool {
+        self.first_geq(id, item) == Some(item)
+    }
+
+    fn push_block(&mut self, items: &[u32]) {
+        let deltas = items.windows(2).map(|i| i[1] - i[0]);
+        let bits = deltas
+            .clone()
+            .map(|i| 32 - i.leading_zeros())
+            .max()
+            .unwrap_or(0);
+
+        self.blocks.push(Block {
+            first: items[0],
+            last: *items.last().unwrap(),
+            bits: bits as u8,
+            offset: self.data.len() as u32,
+        });
+
+        if bits == 0 {
+            return;
+        }
+
+        let mut word = 0u64;
+        let mut filled = 0;
+        for delta in deltas {
+            word |= (delta as u64) << filled;
+            filled += bits;
+            if filled >= 32 {
+                self.data.push(word as u32);
+                word >>= 32;
+                filled -= 32;
+            }
+        }
+        if filled > 0 {
+            self.data.push(word as u32);
+        }
+    }
+
+    /// Decodes the first `len` items of a block into `out`. Returns `None` if the block is
+    /// corrupt
+    fn decode_block(&self, block: &Block, len: usize, out: &mut [u32; BLOCK_SIZE]) -> Option<()> {
+        let bits = block.bits as u32;
+        if bits > 32 {
+            return None;
+        }
+        let mask = (1u64 << bits) - 1;
+
+        out[0] = block.first;
+
+        let mut data = self.data.get(block.offset as usize..)?.iter();
+        let mut word = 0u64;
+        let mut avail = 0;
+        for i in 1..len {
+            if bits == 0 {
+                out[i] = out[i - 1];
+                continue;
+            }
+
+            if avail < bits {
+                word |= (*data.next()? as u64) << avail;
+                avail += 32;
+            }
+            out[i] = out[i - 1].wrapping_add((word & mask) as u32);
+            word >>= bits;
+            avail -= bits;
+        }
+        Some(())
+    }
+
+    #[inline]
+    fn list(&self, id: u32) -> Option<&List> {
+        self.lists.get(id as usize).filter(|i| i.len > 0)
+    }
+
+    /// Returns the blocks of a list or `None` if they exceed the blocks of corrupt postings
+    #[inline]
+    fn list_blocks(&self, id: u32) -> Option<&[Block]> {
+        let list = self.list(id)?;
+        let start = list.first_block as usize;
+        let count = (list.len as usize).div_ceil(BLOCK_SIZE);
+        self.blocks.get(start..start.checked_add(count)?)
+    }
+
+    /// Returns the amount of items in the n-th block of a list
+    #[inline]
+    fn block_len(&self, id: u32, n: usize) -> usize {
+        let len = self.list(id).map(|i| i.len).unwrap_or(0) as usize;
+        (len - n * BLOCK_SIZE).min(BLOCK_SIZE)
+    }
+}
+
+impl Kind for Postings {
+    const KIND: &'static str = "postings.packed";
+}
+
+impl IndexPostings for Postings {
+    type Cursor<'a> = Cursor<'a>;
+
+    #[inline]
+    fn cursor(&self, id: u32) -> Self::Cursor<'_> {
+        let blocks = self.list_blocks(id).unwrap_or_default();
+        Cursor {
+            postings: self,
+            blocks,
+            len: if blocks.is_empty() {
+                0
+            } else {
+                self.posting_size(id)
+            },
+            next_block: 0,
+            buf: [0; BLOCK_SIZE],
+            buf_len: 0,
+            buf_pos: 0,
+        }
+    }
+
+    fn get_posting(&self, id: u32) -> Vec<u32> {
+        let blocks = match self.list_blocks(id) {
+            Some(b) => b,
+            None => return vec![],
+        };
+
+        let mut out = Vec::with_capacity(self.posting_size(id));
+        let mut buf = [0u32; BLOCK_SIZE];
+        for (n, block) in blocks.iter().enumerate() {
+            let len = self.block_len(id, n);
+            // Lists of corrupt postings are empty
+            if self.decode_block(block, len, &mut buf).is_none() {
+                return vec![];
+            }
+            out.extend_from_slice(&buf[..len]);
+        }
+        out
+    }
+
+    #[inline]
+    fn has_id(&self, id: u32) -> bool {
+        self.list(id).is_some()
+    }
+
+    #[inline]
+    fn posting_size(&self, id: u32) -> usize {
+        self.list(id).map(|i| i.len as usize).unwrap_or(0)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.lists.len()
+    }
+}
+
+impl BuildPostings for Postings {
+    type Output = Self;
+    type PostingList = Vec<u32>;
+
+    #[inline]
+    fn from_map(map: HashMap<u32, Self::PostingList>) -> Self {
+        Self::from_map(map)
+    }
+
+    #[inline]
+    fn build(self) -> Self::Output {
+        self
+    }
+}
+
+/// Cursor over a packed postings-list. Decodes one block at a time into a fixed buffer and uses
+/// the blocks skip data to seek.
+pub struct Cursor<'a> {
+    postings: &'a Postings,
+    blocks: &'a [Block],
+    /// Length of the postings-list
+    len: usize,
+    /// Block to decode next
+    next_block: usize,
+    buf: [u32; BLOCK_SIZE],
+    buf_len: usize,
+    buf_pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Decodes the block with the given index into the buffer. Returns `None` if there is no such
+    /// block or it is corrupt
+    fn load_block(&mut self, n: usize) -> Option<()> {
+        let block = self.blocks.get(n)?;
+        let len = (self.len - n * BLOCK_SIZE).min(BLOCK_SIZE);
+        self.postings.decode_block(block, len, &mut self.buf)?;
+        self.buf_len = len;
+        self.buf_pos = 0;
+        self.next_block = n + 1;
+        Some(())
+    }
+}
+
+impl<'a> Iterator for Cursor<'a> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf_pos >= self.buf_len {
+            self.load_block(self.next_block)?;
+        }
+        let item = self.buf[self.buf_pos];
+        self.buf_pos += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let consumed = match self.next_block {
+            0 => 0,
+            n => (n - 1) * BLOCK_SIZE + self.buf_pos,
+        };
+        let len = self.len - consumed;
+        (len, Some(len))
+    }
+}
+
+impl<'a> PostingCursor for Cursor<'a> {
+    fn advance_to(&mut self, target: u32) -> Option<u32> {
+        let in_buf = self.buf_pos < self.buf_len && self.buf[self.buf_len - 1] >= target;
+
+        if !in_buf {
+            // Skip all blocks which only contain smaller items
+            let rest = self.blocks.get(self.next_block..).unwrap_or_default();
+            let skip = rest.partition_point(|b| b.last < target);
+            if self.load_block(self.next_block + skip).is_none() {
+                self.next_block = self.blocks.len();
+                self.buf_pos = self.buf_len;
+                return None;
+            }
+        }
+
+        self.find(|i| *i >= target)
+    }
+}
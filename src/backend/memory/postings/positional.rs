@@ -0,0 +1,302 @@
+use crate::traits::{
+    kind::Kind,
+    postings::{BuildPostings, IndexPositions, IndexPostings, PostingCursor, PostingsMut},
+};
+use compressed_vec::{buffered::BufCVecRef, CVec};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Postings storing the positions of the term within each item
+#[derive(Serialize, Deserialize, Default)]
+pub struct Postings {
+    /// Maps term IDs to positions in `data`
+    index: CVec,
+    /// Contains the items and positions for each term in the format
+    /// `[item_count, (item_id, pos_count, positions..)..]`
+    data: CVec,
+    /// Words in `data` belonging to lists that were replaced by `add_item_positioned`
+    #[serde(skip)]
+    dead: usize,
+}
+
+/// Builder for positional postings
+#[derive(Default)]
+pub struct PositionalBuilder {
+    map: HashMap<u32, Vec<u32>>,
+    positions: HashMap<u32, HashMap<u32, Vec<u32>>>,
+}
+
+impl Postings {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn build(
+        map: HashMap<u32, Vec<u32>>,
+        mut positions: HashMap<u32, HashMap<u32, Vec<u32>>>,
+    ) -> Self {
+        let lists = map.into_iter().map(|(term_id, item_ids)| {
+            let mut term_pos = positions.remove(&term_id).unwrap_or_default();
+
+            // Postings may contain an item once per occurrence
+            let mut seen = HashSet::with_capacity(item_ids.len());
+            let items = item_ids
+                .into_iter()
+                .filter(|i| seen.insert(*i))
+                .map(|item| {
+                    let mut pos = term_pos.remove(&item).unwrap_or_default();
+                    pos.sort_unstable();
+                    (item, pos)
+                })
+                .collect();
+            (term_id, items)
+        });
+        Self::from_lists(lists)
+    }
+
+    /// Builds the postings from `(term_id, [(item_id, positions)..])` lists
+    fn from_lists<I>(lists: I) -> Self
+    where
+        I: Iterator<Item = (u32, Vec<(u32, Vec<u32>)>)>,
+    {
+        let mut index = CVec::new();
+
+        // Terms without postings point to an empty list at the beginning
+        let mut data = CVec::new();
+        data.push(0);
+
+        for (term_id, items) in lists.sorted_by(|a, b| a.0.cmp(&b.0)) {
+            for _ in index.len() as u32..term_id {
+                index.push(0);
+            }
+            index.push(data.len() as u32);
+
+            data.push(items.len() as u32);
+            for (item, pos) in items {
+                data.push(item);
+                data.push(pos.len() as u32);
+                data.extend(pos);
+            }
+        }
+
+        Self {
+            index,
+            data,
+            dead: 0,
+        }
+    }
+
+    /// Calls `f` for each item of the term with the given ID. The second argument of `f` yields
+    /// the term's positions in the item
+    fn for_each<F>(&self, id: u32, mut f: F)
+    where
+        F: FnMut(u32, &mut dyn Iterator<Item = u32>),
+    {
+        let start = match self.index.get(id as usize) {
+            Some(s) => s as usize,
+            None => return,
+        };
+
+        let mut buf = BufCVecRef::new(&self.data);
+        let item_count = *buf.get_buffered(start).unwrap();
+
+        let mut pos = start + 1;
+        for _ in 0..item_count {
+            let item = *buf.get_buffered(pos).unwrap();
+            let pos_count = *buf.get_buffered(pos + 1).unwrap() as usize;
+            pos += 2;
+
+            let mut positions = (pos..pos + pos_count).map(|i| self.data.get(i).unwrap());
+            f(item, &mut positions);
+            pos += pos_count;
+        }
+    }
+
+    #[inline]
+    fn item_count(&self, id: u32) -> usize {
+        self.index
+            .get(id as usize)
+            .and_then(|s| self.data.get(s as usize))
+            .unwrap_or(0) as usize
+    }
+}
+
+impl Kind for Postings {
+    const KIND: &'static str = "postings.positional";
+}
+
+impl IndexPostings for Postings {
+    type Cursor<'a> = Cursor<'a>;
+
+    #[inline]
+    fn cursor(&self, id: u32) -> Self::Cursor<'_> {
+        let start = self.index.get(id as usize).unwrap_or(0) as usize;
+        Cursor {
+            buf: BufCVecRef::new(&self.data),
+            pos: start + 1,
+            remaining: self.item_count(id),
+        }
+    }
+
+    #[inline]
+    fn get_posting(&self, id: u32) -> Vec<u32> {
+        let mut out = Vec::with_capacity(self.item_count(id));
+        self.for_each(id, |item, _| out.push(item));
+        out
+    }
+
+    #[inline]
+    fn get_term_freqs(&self, id: u32) -> Vec<(u32, u32)> {
+        let mut out = Vec::with_capacity(self.item_count(id));
+        self.for_each(id, |item, positions| {
+            out.push((item, positions.count() as u32))
+        });
+        out
+    }
+
+    #[inline]
+    fn has_id(&self, id: u32) -> bool {
+        self.item_count(id) > 0
+    }
+
+    #[inline]
+    fn posting_size(&self, id: u32) -> usize {
+        self.item_count(id)
+    }
+
+    #[inline]
+    fn term_positions(&self, id: u32) -> Option<Vec<(u32, Vec<u32>)>> {
+        Some(self.get_positions(id))
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+}
+
+impl IndexPositions for Postings {
+    #[inline]
+    fn get_positions(&self, id: u32) -> Vec<(u32, Vec<u32>)> {
+        let mut out = Vec::with_capacity(self.item_count(id));
+        self.for_each(id, |item, positions| out.push((item, positions.collect())));
+        out
+    }
+}
+
+impl PostingsMut for Postings {
+    #[inline]
+    fn add_item(&mut self, id: u32, item: u32) {
+        self.add_item_positioned(id, item, &[]);
+    }
+
+    /// Appends a copy of the list including `item` to the data. The space of the old list gets
+    /// reclaimed by `remap_items`, which runs once half of the data is dead
+    fn add_item_positioned(&mut self, id: u32, item: u32, positions: &[u32]) {
+        let mut items = self.get_positions(id);
+        if !items.is_empty() {
+            self.dead += 1 + items.iter().map(|(_, pos)| pos.len() + 2).sum::<usize>();
+        }
+        let mut positions = positions.to_vec();
+        positions.sort_unstable();
+        items.push((item, positions));
+
+        // Terms without postings point to an empty list at the beginning
+        if self.data.is_empty() {
+            self.data.push(0);
+        }
+        for _ in self.index.len() as u32..id {
+            self.index.push(0);
+        }
+
+        let start = self.data.len() as u32;
+        self.data.push(items.len() as u32);
+        for (item, pos) in items {
+            self.data.push(item);
+            self.data.push(pos.len() as u32);
+            self.data.extend(pos);
+        }
+
+        if self.index.len() as u32 == id {
+            self.index.push(start);
+        } else {
+            self.index.set(id as usize, start);
+        }
+
+        if self.dead > self.data.len() / 2 {
+            self.remap_items(Some);
+        }
+    }
+
+    fn remap_items<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> Option<u32>,
+    {
+        let lists: Vec<_> = (0..self.index.len() as u32)
+            .filter_map(|id| {
+                let items: Vec<_> = self
+                    .get_positions(id)
+                    .into_iter()
+                    .filter_map(|(item, pos)| Some((f(item)?, pos)))
+                    .collect();
+                (!items.is_empty()).then_some((id, items))
+            })
+            .collect();
+        *self = Self::from_lists(lists.into_iter());
+    }
+}
+
+impl BuildPostings for PositionalBuilder {
+    type Output = Postings;
+    type PostingList = Vec<u32>;
+
+    #[inline]
+    fn from_map(map: HashMap<u32, Self::PostingList>) -> Self {
+        Self {
+            map,
+            positions: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    fn set_positions(&mut self, positions: HashMap<u32, HashMap<u32, Vec<u32>>>) {
+        self.positions = positions;
+    }
+
+    #[inline]
+    fn build(self) -> Self::Output {
+        Postings::build(self.map, self.positions)
+    }
+}
+
+/// Cursor over the items of a positional postings-list
+pub struct Cursor<'a> {
+    buf: BufCVecRef<'a>,
+    pos: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Cursor<'a> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let item = *self.buf.get_buffered(self.pos)?;
+        let pos_count = *self.buf.get_buffered(self.pos + 1)? as usize;
+        self.pos += 2 + pos_count;
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> PostingCursor for Cursor<'a> {}
@@ -0,0 +1,256 @@
+use crate::traits::{
+    kind::Kind,
+    postings::{BuildPostings, IndexPostings, PostingCursor, PostingsMut},
+};
+use compressed_vec::{buffered::BufCVecRef, CVec};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Compressed postings storing each item once along with its term frequency.
+/// Built from postings lists containing an item once per occurrence of the term,
+/// so `BuildOption::UniquePostings` must not be set.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Postings {
+    /// Maps term IDs to positions in `data`
+    index: CVec,
+    /// Contains `[item_count, (item_id, tf)..]` for each term
+    data: CVec,
+    /// Words in `data` belonging to lists that were replaced by `add_item_positioned`
+    #[serde(skip)]
+    dead: usize,
+}
+
+impl Postings {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_map(map: HashMap<u32, Vec<u32>>) -> Self {
+        Self::from_freqs(map.into_iter().map(|(k, v)| (k, Self::count(v))))
+    }
+
+    /// Builds the postings from `(term_id, [(item_id, tf)..])` lists
+    fn from_freqs<I>(lists: I) -> Self
+    where
+        I: Iterator<Item = (u32, Vec<(u32, u32)>)>,
+    {
+        let mut index = CVec::new();
+
+        // Terms without postings point to an empty list at the beginning
+        let mut data = CVec::new();
+        data.push(0);
+
+        for (term_id, freqs) in lists.sorted_by(|a, b| a.0.cmp(&b.0)) {
+            for _ in index.len() as u32..term_id {
+                index.push(0);
+            }
+            index.push(data.len() as u32);
+
+            data.push(freqs.len() as u32);
+            for (item, tf) in freqs {
+                data.push(item);
+                data.push(tf);
+            }
+        }
+
+        Self {
+            index,
+            data,
+            dead: 0,
+        }
+    }
+
+    /// Counts the occurrences of each item while keeping the order of their first occurrence
+    fn count(item_ids: Vec<u32>) -> Vec<(u32, u32)> {
+        let mut pos: HashMap<u32, usize> = HashMap::with_capacity(item_ids.len());
+        let mut out: Vec<(u32, u32)> = Vec::with_capacity(item_ids.len());
+        for item in item_ids {
+            let p = *pos.entry(item).or_insert_with(|| {
+                out.push((item, 0));
+                out.len() - 1
+            });
+            out[p].1 += 1;
+        }
+        out
+    }
+
+    #[inline]
+    fn get(&self, id: u32) -> Option<Vec<(u32, u32)>> {
+        let start = self.index.get(id as usize)? as usize;
+
+        let mut buf_vec = BufCVecRef::new(&self.data);
+        let len = *buf_vec.get_buffered(start)? as usize;
+        if len == 0 {
+            return None;
+        }
+
+        let mut out = Vec::with_capacity(len);
+        for i in 0..len {
+            let pos = start + 1 + i * 2;
+            let item = *buf_vec.get_buffered(pos)?;
+            let tf = *buf_vec.get_buffered(pos + 1)?;
+            out.push((item, tf));
+        }
+
+        Some(out)
+    }
+
+    #[inline]
+    fn item_count(&self, id: u32) -> usize {
+        self.index
+            .get(id as usize)
+            .and_then(|s| self.data.get(s as usize))
+            .unwrap_or(0) as usize
+    }
+}
+
+impl Kind for Postings {
+    const KIND: &'static str = "postings.tf";
+}
+
+impl IndexPostings for Postings {
+    type Cursor<'a> = Cursor<'a>;
+
+    #[inline]
+    fn cursor(&self, id: u32) -> Self::Cursor<'_> {
+        let start = self.index.get(id as usize).unwrap_or(0) as usize;
+        Cursor {
+            buf: BufCVecRef::new(&self.data),
+            pos: start + 1,
+            remaining: self.item_count(id),
+        }
+    }
+
+    #[inline]
+    fn get_posting(&self, id: u32) -> Vec<u32> {
+        self.get(id)
+            .map(|i| i.into_iter().map(|i| i.0).collect())
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn get_term_freqs(&self, id: u32) -> Vec<(u32, u32)> {
+        self.get(id).unwrap_or_default()
+    }
+
+    #[inline]
+    fn has_id(&self, id: u32) -> bool {
+        self.item_count(id) > 0
+    }
+
+    #[inline]
+    fn posting_size(&self, id: u32) -> usize {
+        self.item_count(id)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+}
+
+impl PostingsMut for Postings {
+    #[inline]
+    fn add_item(&mut self, id: u32, item: u32) {
+        self.add_item_positioned(id, item, &[]);
+    }
+
+    /// Appends a copy of the list including `item` to the data, using the amount of positions as
+    /// term frequency. The space of the old list gets reclaimed by `remap_items`, which runs once
+    /// half of the data is dead
+    fn add_item_positioned(&mut self, id: u32, item: u32, positions: &[u32]) {
+        let mut freqs = self.get(id).unwrap_or_default();
+        if !freqs.is_empty() {
+            self.dead += freqs.len() * 2 + 1;
+        }
+        freqs.push((item, positions.len().max(1) as u32));
+
+        // Terms without postings point to an empty list at the beginning
+        if self.data.is_empty() {
+            self.data.push(0);
+        }
+        for _ in self.index.len() as u32..id {
+            self.index.push(0);
+        }
+
+        let start = self.data.len() as u32;
+        self.data.push(freqs.len() as u32);
+        for (item, tf) in freqs {
+            self.data.push(item);
+            self.data.push(tf);
+        }
+
+        if self.index.len() as u32 == id {
+            self.index.push(start);
+        } else {
+            self.index.set(id as usize, start);
+        }
+
+        if self.dead > self.data.len() / 2 {
+            self.remap_items(Some);
+        }
+    }
+
+    fn remap_items<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> Option<u32>,
+    {
+        let lists: Vec<_> = (0..self.index.len() as u32)
+            .filter_map(|id| {
+                let freqs: Vec<_> = self
+                    .get(id)?
+                    .into_iter()
+                    .filter_map(|(item, tf)| Some((f(item)?, tf)))
+                    .collect();
+                (!freqs.is_empty()).then_some((id, freqs))
+            })
+            .collect();
+        *self = Self::from_freqs(lists.into_iter());
+    }
+}
+
+impl BuildPostings for Postings {
+    type Output = Self;
+    type PostingList = Vec<u32>;
+
+    #[inline]
+    fn from_map(map: HashMap<u32, Self::PostingList>) -> Self {
+        Self::from_map(map)
+    }
+
+    #[inline]
+    fn build(self) -> Self::Output {
+        self
+    }
+}
+
+/// Cursor over the items of a term frequency postings-list
+pub struct Cursor<'a> {
+    buf: BufCVecRef<'a>,
+    pos: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Cursor<'a> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let item = *self.buf.get_buffered(self.pos)?;
+        self.pos += 2;
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> PostingCursor for Cursor<'a> {}
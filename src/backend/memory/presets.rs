@@ -8,6 +8,15 @@ use super::{
 pub type NGIndex<const N: usize, S> =
     MemBackend<String, S, FixDict<N>, storage::default::Storage<S>, postings::compressed::Postings>;
 
+pub type NGIndexBuilder<const N: usize, S> = MemIndexBuilder<
+    NGIndex<N, S>,
+    String,
+    S,
+    FixDict<N>,
+    storage::default::Storage<S>,
+    postings::compressed::Postings,
+>;
+
 // Simple Index
 pub type Simple<T, S> = MemBackend<
     T,
@@ -44,6 +53,24 @@ pub type SimpleCompressedBuilder<T, S> = MemIndexBuilder<
     postings::compressed::Postings,
 >;
 
+// Simple Index with a transducer dictionary
+pub type SimpleFst<S> = MemBackend<
+    String,
+    S,
+    dict::fst::FstDict,
+    storage::default::Storage<S>,
+    postings::default::Postings,
+>;
+
+pub type SimpleFstBuilder<S> = MemIndexBuilder<
+    SimpleFst<S>,
+    String,
+    S,
+    dict::fst::FstDictBuilder,
+    storage::default::Storage<S>,
+    postings::default::Postings,
+>;
+
 // Compressed u32-index
 pub type CompressedU32<T> = MemBackend<
     T,
@@ -61,3 +88,57 @@ pub type CompressedU32Builder<T> = MemIndexBuilder<
     storage::c_u32::U32Storage,
     postings::compressed::Postings,
 >;
+
+// Bitmap Index
+pub type SimpleBitmap<T, S> = MemBackend<
+    T,
+    S,
+    dict::default::Dictionary<T>,
+    storage::default::Storage<S>,
+    postings::bitmap::Postings,
+>;
+
+pub type SimpleBitmapBuilder<T, S> = MemIndexBuilder<
+    SimpleBitmap<T, S>,
+    T,
+    S,
+    dict::default::Dictionary<T>,
+    storage::default::Storage<S>,
+    postings::bitmap::Postings,
+>;
+
+// Term frequency Index
+pub type TermFreq<T, S> = MemBackend<
+    T,
+    S,
+    dict::default::Dictionary<T>,
+    storage::default::Storage<S>,
+    postings::tf::Postings,
+>;
+
+pub type TermFreqBuilder<T, S> = MemIndexBuilder<
+    TermFreq<T, S>,
+    T,
+    S,
+    dict::default::Dictionary<T>,
+    storage::default::Storage<S>,
+    postings::tf::Postings,
+>;
+
+// Positional Index
+pub type Positional<T, S> = MemBackend<
+    T,
+    S,
+    dict::default::Dictionary<T>,
+    storage::default::Storage<S>,
+    postings::positional::Postings,
+>;
+
+pub type PositionalBuilder<T, S> = MemIndexBuilder<
+    Positional<T, S>,
+    T,
+    S,
+    dict::default::Dictionary<T>,
+    storage::default::Storage<S>,
+    postings::positional::PositionalBuilder,
+>;
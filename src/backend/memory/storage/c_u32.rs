@@ -1,4 +1,7 @@
-use crate::traits::{build::ItemMod, storage::IndexStorage};
+use crate::{
+    error::Error,
+    traits::{build::ItemMod, kind::Kind, storage::IndexStorage},
+};
 use compressed_vec::CVec;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +25,10 @@ impl U32Storage {
     }
 }
 
+impl Kind for U32Storage {
+    const KIND: &'static str = "storage.c_u32";
+}
+
 impl IndexStorage<u32> for U32Storage {
     #[inline]
     fn get_item(&self, id: u32) -> Option<u32> {
@@ -41,7 +48,8 @@ impl IndexStorage<u32> for U32Storage {
 
 impl ItemMod<u32> for U32Storage {
     #[inline]
-    fn set_item(&mut self, id: u32, new: u32) {
+    fn set_item(&mut self, id: u32, new: u32) -> Result<(), Error> {
         self.data.set(id as usize, new);
+        Ok(())
     }
 }
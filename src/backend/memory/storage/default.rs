@@ -1,7 +1,11 @@
-use crate::traits::{
-    build::ItemMod,
-    deser::DeSer,
-    storage::{BuildIndexStorage, IndexStorage, IndexStorageMod},
+use crate::{
+    error::Error,
+    traits::{
+        build::ItemMod,
+        deser::DeSer,
+        kind::Kind,
+        storage::{BuildIndexStorage, IndexStorage, IndexStorageMod},
+    },
 };
 use serde::{Deserialize, Serialize};
 use st_file::{
@@ -29,15 +33,19 @@ impl<S> Storage<S> {
 
 impl<S: DeSer> Storage<S> {
     #[inline]
-    pub(crate) fn insert(&mut self, item: S) -> u32 {
-        self.data.insert(&item.encode_vec()) as u32
+    pub(crate) fn insert(&mut self, item: S) -> Result<u32, Error> {
+        Ok(self.data.insert(&item.encode_vec()?) as u32)
     }
 }
 
+impl<S> Kind for Storage<S> {
+    const KIND: &'static str = "storage.default";
+}
+
 impl<S: DeSer> IndexStorage<S> for Storage<S> {
     #[inline]
     fn get_item(&self, id: u32) -> Option<S> {
-        S::decode_vec(self.data.get(id as usize)?)
+        S::decode_vec(self.data.get(id as usize)?).ok()
     }
 
     #[inline]
@@ -54,7 +62,28 @@ impl<S: DeSer> IndexStorage<S> for Storage<S> {
 impl<S: DeSer> IndexStorageMod<S> for Storage<S> {
     #[inline]
     fn set_item(&mut self, id: u32, new: S) -> bool {
-        self.data.replace(id as usize, &new.encode_vec()).is_some()
+        match new.encode_vec() {
+            Ok(encoded) => self.data.replace(id as usize, &encoded).is_some(),
+            Err(_) => false,
+        }
+    }
+
+    #[inline]
+    fn push_item(&mut self, item: S) -> Result<u32, Error> {
+        self.insert(item)
+    }
+
+    fn retain_items<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> bool,
+    {
+        let mut data = MemFile::new();
+        for id in 0..self.data.len() {
+            if f(id as u32) {
+                data.insert(self.data.get(id).unwrap());
+            }
+        }
+        self.data = data;
     }
 }
 
@@ -66,7 +95,7 @@ impl<S: DeSer> BuildIndexStorage<S> for Storage<S> {
     }
 
     #[inline]
-    fn insert(&mut self, item: S) -> u32 {
+    fn insert(&mut self, item: S) -> Result<u32, Error> {
         self.insert(item)
     }
 
@@ -91,8 +120,9 @@ where
     S: DeSer,
 {
     #[inline]
-    fn set_item(&mut self, id: u32, new: S) {
-        let encoded = new.encode_vec();
+    fn set_item(&mut self, id: u32, new: S) -> Result<(), Error> {
+        let encoded = new.encode_vec()?;
         self.data.replace(id as usize, &encoded);
+        Ok(())
     }
 }
@@ -1,4 +1,10 @@
-use crate::traits::storage::{BuildIndexStorage, IndexStorage};
+use crate::{
+    error::Error,
+    traits::{
+        kind::Kind,
+        storage::{BuildIndexStorage, IndexStorage},
+    },
+};
 use serde::{Deserialize, Serialize};
 
 /// An in-memory storage for index items
@@ -24,6 +30,10 @@ impl Storage {
     }
 }
 
+impl Kind for Storage {
+    const KIND: &'static str = "storage.u32map";
+}
+
 impl IndexStorage<u32> for Storage {
     #[inline]
     fn get_item(&self, id: u32) -> Option<u32> {
@@ -49,8 +59,8 @@ impl BuildIndexStorage<u32> for Storage {
     }
 
     #[inline]
-    fn insert(&mut self, item: u32) -> u32 {
-        self.insert(item)
+    fn insert(&mut self, item: u32) -> Result<u32, Error> {
+        Ok(self.insert(item))
     }
 
     #[inline]
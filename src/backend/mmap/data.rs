@@ -0,0 +1,125 @@
+use memmap2::Mmap;
+use std::{ops::Deref, sync::Arc};
+
+/// Raw bytes of an mmap index. Either mapped from a file or held in memory
+pub enum Source {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Deref for Source {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Source::Mapped(m) => m,
+            Source::Owned(v) => v,
+        }
+    }
+}
+
+/// A range of bytes within a `Source`
+#[derive(Clone)]
+pub(crate) struct Region {
+    data: Arc<Source>,
+    start: usize,
+    len: usize,
+}
+
+impl Region {
+    #[inline]
+    pub(crate) fn new(data: Arc<Source>, start: usize, len: usize) -> Option<Self> {
+        (start.checked_add(len)? <= data.len()).then(|| Self { data, start, len })
+    }
+
+    #[inline]
+    pub(crate) fn bytes(&self) -> &[u8] {
+        &self.data[self.start..self.start + self.len]
+    }
+
+    #[inline]
+    pub(crate) fn read_u64(&self, pos: usize) -> Option<u64> {
+        let b = self.bytes().get(pos..pos.checked_add(8)?)?;
+        Some(u64::from_le_bytes(b.try_into().unwrap()))
+    }
+}
+
+/// A list of variable sized byte entries stored in a `Region`.
+///
+/// Layout: `[count: u64][offsets: u64 * (count + 1)][entry data]`
+#[derive(Clone)]
+pub(crate) struct Table {
+    region: Region,
+    count: usize,
+}
+
+impl Table {
+    pub(crate) fn new(region: Region) -> Option<Self> {
+        let count = usize::try_from(region.read_u64(0)?).ok()?;
+        let table = Self { region, count };
+        // Validate the last offset so later lookups can't go out of bounds
+        let end = table.offset(count)?;
+        (table.data_start()?.checked_add(end)? <= table.region.len).then_some(table)
+    }
+
+    /// Returns the entry at `pos`
+    #[inline]
+    pub(crate) fn get(&self, pos: usize) -> Option<&[u8]> {
+        if pos >= self.count {
+            return None;
+        }
+        let data_start = self.data_start()?;
+        let start = data_start.checked_add(self.offset(pos)?)?;
+        let end = data_start.checked_add(self.offset(pos + 1)?)?;
+        self.region.bytes().get(start..end)
+    }
+
+    /// Returns the amount of entries in the table
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the offset of the entry at `pos`. The count is read from the data, so all
+    /// positions are computed with checked arithmetic
+    #[inline]
+    fn offset(&self, pos: usize) -> Option<usize> {
+        let pos = pos.checked_mul(8)?.checked_add(8)?;
+        usize::try_from(self.region.read_u64(pos)?).ok()
+    }
+
+    #[inline]
+    fn data_start(&self) -> Option<usize> {
+        self.count.checked_add(1)?.checked_mul(8)?.checked_add(8)
+    }
+}
+
+/// Writes a `Table` for the given entries
+pub(crate) fn write_table<'a, I>(out: &mut Vec<u8>, entries: I)
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let entries: Vec<_> = entries.into_iter().collect();
+
+    out.extend((entries.len() as u64).to_le_bytes());
+
+    let mut offset = 0u64;
+    out.extend(offset.to_le_bytes());
+    for entry in entries.iter() {
+        offset += entry.len() as u64;
+        out.extend(offset.to_le_bytes());
+    }
+
+    for entry in entries {
+        out.extend(entry);
+    }
+}
+
+/// Reads a little endian encoded u32 slice
+#[inline]
+pub(crate) fn read_u32_at(data: &[u8], pos: usize) -> Option<u32> {
+    let start = pos.checked_mul(4)?;
+    let b = data.get(start..start.checked_add(4)?)?;
+    Some(u32::from_le_bytes(b.try_into().unwrap()))
+}
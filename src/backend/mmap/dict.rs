@@ -0,0 +1,79 @@
+use super::data::{read_u32_at, Region, Table};
+use crate::{
+    traits::{dict_item::DictItem, dictionary::IndexDictionary, kind::Kind},
+    utils::bin_search::generic_binary_search,
+};
+use std::{cmp::Ordering, marker::PhantomData};
+
+/// Dictionary reading its terms lazily from mapped bytes
+pub struct MmapDict<T> {
+    terms: Table,
+    sort_index: Region,
+    p: PhantomData<T>,
+}
+
+impl<T: DictItem> MmapDict<T> {
+    #[inline]
+    pub(crate) fn new(terms: Table, sort_index: Region) -> Option<Self> {
+        (Some(sort_index.bytes().len()) == terms.len().checked_mul(4)).then(|| Self {
+            terms,
+            sort_index,
+            p: PhantomData,
+        })
+    }
+
+    /// Returns the term ID at the given position of the sorted term list
+    #[inline]
+    fn sorted_id(&self, pos: usize) -> Option<u32> {
+        read_u32_at(self.sort_index.bytes(), pos)
+    }
+}
+
+impl<T> Kind for MmapDict<T> {
+    const KIND: &'static str = "mmap.dict";
+}
+
+impl<T: DictItem> IndexDictionary<T> for MmapDict<T> {
+    #[inline]
+    fn get_id<F: Into<T>>(&self, term: F) -> Option<u32> {
+        let term = term.into();
+
+        // Terms that can't be read stop the search, as the mapped data is corrupt
+        let mut corrupt = false;
+        let res = generic_binary_search((), self.len(), |_, i| {
+            match self.sorted_id(i).and_then(|id| self.get_term(id)) {
+                Some(bterm) => (bterm.cmp(&term), ()),
+                None => {
+                    corrupt = true;
+                    (Ordering::Equal, ())
+                }
+            }
+        })
+        .ok()?
+        .0;
+        if corrupt {
+            return None;
+        }
+        self.sorted_id(res)
+    }
+
+    #[inline]
+    fn get_term(&self, id: u32) -> Option<T> {
+        T::decode_vec(self.terms.get(id as usize)?).ok()
+    }
+
+    #[inline]
+    fn has_term_id(&self, id: u32) -> bool {
+        (id as usize) < self.terms.len()
+    }
+
+    #[inline]
+    fn sort_pos(&self, pos: usize) -> Option<u32> {
+        self.sorted_id(pos)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.terms.len()
+    }
+}
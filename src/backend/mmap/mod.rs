@@ -0,0 +1,356 @@
+pub mod data;
+pub mod dict;
+pub mod postings;
+pub mod storage;
+
+use crate::{
+    error::Error,
+    stats::IndexStats,
+    traits::{
+        backend::Backend, deser::DeSer, dict_item::DictItem, dictionary::IndexDictionary,
+        postings::IndexPostings, storage::IndexStorage,
+    },
+    utils::header::Header,
+};
+use data::{write_table, Region, Source, Table};
+use dict::MmapDict;
+use memmap2::Mmap;
+use postings::MmapPostings;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+    sync::Arc,
+};
+use storage::MmapStorage;
+
+/// Read-only index backend operating on a memory-mapped file. Nothing gets deserialized
+/// upfront; dictionary, postings and storage read their data lazily from the mapped bytes
+/// which allows multiple processes to share one page-cached index.
+pub struct MmapBackend<T, S> {
+    data: Arc<Source>,
+    /// Start of the index data within `data`
+    base: usize,
+    dict: MmapDict<T>,
+    postings_list: Vec<MmapPostings>,
+    storage: MmapStorage<S>,
+    stats: Option<IndexStats>,
+}
+
+/// Version of the encoded index format
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Position of a section within the encoded index
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+struct Section {
+    start: u64,
+    len: u64,
+}
+
+/// Describes where each component is located in the encoded index
+#[derive(Serialize, Deserialize, Default)]
+struct Layout {
+    version: u32,
+    dict_terms: Section,
+    dict_sort: Section,
+    storage: Section,
+    stats: Section,
+    postings: Vec<Section>,
+}
+
+impl<T, S> MmapBackend<T, S>
+where
+    T: DictItem,
+    S: DeSer,
+{
+    /// Creates a new mmap backend out of the given source bytes
+    #[inline]
+    pub fn from_source(data: Source) -> Result<Self, Error> {
+        Self::from_source_at(data, 0)
+    }
+
+    /// Creates a new mmap backend out of the given source bytes with the index data starting
+    /// at `base`
+    pub fn from_source_at(data: Source, base: usize) -> Result<Self, Error> {
+        let data = Arc::new(data);
+
+        let header_len = data
+            .get(base..base.saturating_add(8))
+            .map(|i| u64::from_le_bytes(i.try_into().unwrap()) as usize)
+            .ok_or(Error::MissingComponent("layout"))?;
+        let layout: Layout = data
+            .get(base + 8..(base + 8).saturating_add(header_len))
+            .ok_or_else(|| Error::Corrupt("layout exceeds data".to_string()))
+            .and_then(|i| Ok(bincode::deserialize(i)?))?;
+
+        if layout.version != FORMAT_VERSION {
+            return Err(Error::VersionMismatch {
+                expected: FORMAT_VERSION,
+                found: layout.version,
+            });
+        }
+
+        let region = |s: Section, name: &'static str| {
+            base.checked_add(s.start as usize)
+                .and_then(|start| Region::new(data.clone(), start, s.len as usize))
+                .ok_or_else(|| Error::Corrupt(format!("{name} exceeds data")))
+        };
+        // Tables always contain their length, so empty sections are missing
+        let table = |s: Section, name: &'static str| {
+            if s.len == 0 {
+                return Err(Error::MissingComponent(name));
+            }
+            Table::new(region(s, name)?)
+                .ok_or_else(|| Error::Corrupt(format!("invalid {name} table")))
+        };
+
+        let dict = MmapDict::new(
+            table(layout.dict_terms, "dictionary terms")?,
+            region(layout.dict_sort, "dictionary sort index")?,
+        )
+        .ok_or_else(|| Error::Corrupt("dictionary sort index doesn't match terms".to_string()))?;
+        let storage = MmapStorage::new(table(layout.storage, "storage")?);
+        let postings_list = layout
+            .postings
+            .iter()
+            .map(|s| Ok(MmapPostings::new(table(*s, "postings")?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // Statistics are small enough to be deserialized upfront
+        let stats = match layout.stats.len {
+            0 => None,
+            _ => Some(bincode::deserialize(
+                region(layout.stats, "stats")?.bytes(),
+            )?),
+        };
+
+        Ok(Self {
+            data,
+            base,
+            dict,
+            postings_list,
+            storage,
+            stats,
+        })
+    }
+
+    /// Maps the given file into memory like `open` but additionally verifies the checksum of the
+    /// whole payload
+    #[inline]
+    pub fn open_verified<P: AsRef<Path>>(file: P) -> Result<Self, Error> {
+        Self::map_file(file, true)
+    }
+
+    fn map_file<P: AsRef<Path>>(file: P, verify: bool) -> Result<Self, Error> {
+        let file = File::open(file)?;
+        // Safety: The index file is treated as read-only and must not be modified while mapped
+        let map = unsafe { Mmap::map(&file) }?;
+
+        let header = Header::read(&map[..])?;
+        header.check_types::<Self, T, S>()?;
+        let base = header.encoded_len();
+        let payload = map.get(base..).unwrap_or_default();
+        if verify {
+            header.check_payload(payload)?;
+        } else {
+            header.check_len(payload.len() as u64)?;
+        }
+
+        Self::from_source_at(Source::Mapped(map), base)
+    }
+
+    /// Converts any other backend into an in-memory `MmapBackend`
+    pub fn from_backend<B>(backend: &B) -> Result<Self, Error>
+    where
+        B: Backend<T, S>,
+    {
+        Self::from_source(Source::Owned(Self::encode_backend(backend)?))
+    }
+
+    /// Encodes any other backend into a file readable by `MmapBackend::open`
+    pub fn write_backend<B, W>(backend: &B, mut out: W) -> Result<(), Error>
+    where
+        B: Backend<T, S>,
+        W: Write,
+    {
+        let encoded = Self::encode_backend(backend)?;
+        Header::new::<Self, T, S>(&encoded).write(&mut out)?;
+        out.write_all(&encoded)?;
+        Ok(())
+    }
+
+    /// Encodes any other backend into the format used by `MmapBackend`. Deleted items are left
+    /// out and the IDs of the remaining items move up like in `BackendMut::compact`
+    pub fn encode_backend<B>(backend: &B) -> Result<Vec<u8>, Error>
+    where
+        B: Backend<T, S>,
+    {
+        let dict = backend.dict();
+        let term_count = dict.len() as u32;
+
+        let terms: Vec<T> = (0..term_count)
+            .map(|i| dict.get_term(i).ok_or(Error::MissingComponent("term")))
+            .collect::<Result<_, _>>()?;
+        let enc_terms: Vec<_> = terms
+            .iter()
+            .map(|i| i.encode_vec())
+            .collect::<Result<_, _>>()?;
+        let mut dict_terms = vec![];
+        write_table(&mut dict_terms, enc_terms.iter().map(|i| i.as_slice()));
+
+        let mut sort_index: Vec<u32> = (0..term_count).collect();
+        sort_index.sort_by(|a, b| terms[*a as usize].cmp(&terms[*b as usize]));
+        let dict_sort: Vec<u8> = sort_index.iter().flat_map(|i| i.to_le_bytes()).collect();
+
+        let storage = backend.storage();
+        let kept: Vec<u32> = (0..storage.len() as u32)
+            .filter(|i| !backend.is_deleted(*i))
+            .collect();
+        let has_deleted = kept.len() < storage.len();
+        // Maps old item IDs to the ones in the encoded storage
+        let new_id = |id: u32| kept.binary_search(&id).ok().map(|i| i as u32);
+
+        let enc_items: Vec<_> = kept
+            .iter()
+            .map(|&i| {
+                let item = storage.get_item(i).ok_or(Error::MissingComponent("item"))?;
+                item.encode_vec()
+            })
+            .collect::<Result<_, _>>()?;
+        let mut storage_data = vec![];
+        write_table(&mut storage_data, enc_items.iter().map(|i| i.as_slice()));
+
+        let stats = match backend.stats() {
+            Some(stats) if has_deleted => {
+                let mut stats = stats.clone();
+                stats.retain_items(|id| new_id(id).is_some());
+                bincode::serialize(&stats)?
+            }
+            Some(stats) => bincode::serialize(stats)?,
+            None => vec![],
+        };
+
+        let postings: Vec<Vec<u8>> = (0..backend.posting_count() as u32)
+            .map(|p_id| {
+                let postings = backend.postings(p_id).unwrap();
+                let lists: Vec<Vec<u8>> = (0..term_count)
+                    .map(|t_id| {
+                        postings
+                            .get_posting(t_id)
+                            .into_iter()
+                            .filter_map(new_id)
+                            .flat_map(|i| i.to_le_bytes())
+                            .collect()
+                    })
+                    .collect();
+                let mut out = vec![];
+                write_table(&mut out, lists.iter().map(|i| i.as_slice()));
+                out
+            })
+            .collect();
+
+        let mut layout = Layout {
+            version: FORMAT_VERSION,
+            postings: vec![Section::default(); postings.len()],
+            ..Layout::default()
+        };
+
+        // Layout is encoded with fixed size integers so its size doesn't depend on the offsets
+        let header_len = bincode::serialized_size(&layout)?;
+        let mut pos = 8 + header_len;
+        let mut section = |data: &[u8]| {
+            let s = Section {
+                start: pos,
+                len: data.len() as u64,
+            };
+            pos += s.len;
+            s
+        };
+
+        layout.dict_terms = section(&dict_terms);
+        layout.dict_sort = section(&dict_sort);
+        layout.storage = section(&storage_data);
+        layout.stats = section(&stats);
+        for (i, p) in postings.iter().enumerate() {
+            layout.postings[i] = section(p);
+        }
+
+        let mut out = Vec::with_capacity(pos as usize);
+        out.extend(header_len.to_le_bytes());
+        bincode::serialize_into(&mut out, &layout)?;
+        out.extend(dict_terms);
+        out.extend(dict_sort);
+        out.extend(storage_data);
+        out.extend(stats);
+        for p in postings {
+            out.extend(p);
+        }
+        Ok(out)
+    }
+}
+
+impl<T, S> Backend<T, S> for MmapBackend<T, S>
+where
+    T: DictItem,
+    S: DeSer,
+{
+    type Dict = MmapDict<T>;
+    type Storage = MmapStorage<S>;
+    type Postings = MmapPostings;
+
+    #[inline]
+    fn dict(&self) -> &Self::Dict {
+        &self.dict
+    }
+
+    #[inline]
+    fn storage(&self) -> &Self::Storage {
+        &self.storage
+    }
+
+    #[inline]
+    fn postings(&self, id: u32) -> Option<&Self::Postings> {
+        self.postings_list.get(id as usize)
+    }
+
+    #[inline]
+    fn posting_count(&self) -> usize {
+        self.postings_list.len()
+    }
+
+    #[inline]
+    fn stats(&self) -> Option<&IndexStats> {
+        self.stats.as_ref()
+    }
+
+    #[inline]
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.data[self.base..].to_vec())
+    }
+
+    #[inline]
+    fn encode_into<W: Write>(&self, mut out: W) -> Result<(), Error> {
+        out.write_all(&self.data[self.base..])?;
+        Ok(())
+    }
+
+    fn decode_from<R: Read>(mut reader: R) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        Self::from_source(Source::Owned(data))
+    }
+
+    /// Maps the given file into memory. Nothing gets decoded upfront and the payload checksum
+    /// isn't verified, as that would read the whole file. Use `open_verified` to verify it
+    #[inline]
+    fn open<P: AsRef<Path>>(file: P) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Self::map_file(file, false)
+    }
+}
@@ -0,0 +1,103 @@
+use super::data::{read_u32_at, Table};
+use crate::traits::{
+    kind::Kind,
+    postings::{IndexPostings, PostingCursor},
+};
+
+/// Postings reading their lists lazily from mapped bytes. Each table entry
+/// holds the little endian encoded item IDs of one term
+pub struct MmapPostings {
+    lists: Table,
+}
+
+impl MmapPostings {
+    #[inline]
+    pub(crate) fn new(lists: Table) -> Self {
+        Self { lists }
+    }
+
+    #[inline]
+    fn get(&self, id: u32) -> Option<&[u8]> {
+        self.lists.get(id as usize).filter(|i| !i.is_empty())
+    }
+}
+
+impl Kind for MmapPostings {
+    const KIND: &'static str = "mmap.postings";
+}
+
+impl IndexPostings for MmapPostings {
+    type Cursor<'a> = MmapCursor<'a>;
+
+    #[inline]
+    fn cursor(&self, id: u32) -> Self::Cursor<'_> {
+        MmapCursor {
+            data: self.get(id).unwrap_or_default(),
+        }
+    }
+
+    #[inline]
+    fn get_posting(&self, id: u32) -> Vec<u32> {
+        let data = match self.get(id) {
+            Some(d) => d,
+            None => return vec![],
+        };
+        (0..data.len() / 4)
+            .map(|i| read_u32_at(data, i).unwrap())
+            .collect()
+    }
+
+    #[inline]
+    fn has_id(&self, id: u32) -> bool {
+        self.get(id).is_some()
+    }
+
+    #[inline]
+    fn posting_size(&self, id: u32) -> usize {
+        self.get(id).map(|i| i.len() / 4).unwrap_or(0)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.lists.len()
+    }
+}
+
+/// Cursor over a postings-list in mapped bytes
+pub struct MmapCursor<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for MmapCursor<'a> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = read_u32_at(self.data, 0)?;
+        self.data = &self.data[4..];
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.data.len() / 4;
+        (len, Some(len))
+    }
+}
+
+impl<'a> PostingCursor for MmapCursor<'a> {
+    #[inline]
+    fn advance_to(&mut self, target: u32) -> Option<u32> {
+        let (mut left, mut right) = (0, self.data.len() / 4);
+        while left < right {
+            let mid = left + (right - left) / 2;
+            if read_u32_at(self.data, mid).unwrap() < target {
+                left = mid + 1;
+            } else {
+                right = mid;
+            }
+        }
+        self.data = &self.data[left * 4..];
+        self.next()
+    }
+}
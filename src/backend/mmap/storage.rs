@@ -0,0 +1,40 @@
+use super::data::Table;
+use crate::traits::{deser::DeSer, kind::Kind, storage::IndexStorage};
+use std::marker::PhantomData;
+
+/// Storage reading its items lazily from mapped bytes
+pub struct MmapStorage<S> {
+    items: Table,
+    p: PhantomData<S>,
+}
+
+impl<S: DeSer> MmapStorage<S> {
+    #[inline]
+    pub(crate) fn new(items: Table) -> Self {
+        Self {
+            items,
+            p: PhantomData,
+        }
+    }
+}
+
+impl<S> Kind for MmapStorage<S> {
+    const KIND: &'static str = "mmap.storage";
+}
+
+impl<S: DeSer> IndexStorage<S> for MmapStorage<S> {
+    #[inline]
+    fn get_item(&self, id: u32) -> Option<S> {
+        S::decode_vec(self.items.get(id as usize)?).ok()
+    }
+
+    #[inline]
+    fn has_item(&self, id: u32) -> bool {
+        (id as usize) < self.items.len()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+}
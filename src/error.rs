@@ -7,6 +7,27 @@ use std::{
 pub enum Error {
     UTF8Error,
     Bincode(bincode::Error),
+    /// Reading or writing index data failed
+    Io(std::io::Error),
+    /// The index data is malformed
+    Corrupt(String),
+    /// The index data was written in an incompatible format version
+    VersionMismatch {
+        expected: u32,
+        found: u32,
+    },
+    /// A required part of the index is missing
+    MissingComponent(&'static str),
+    /// The index data was written with a different component type
+    TypeMismatch {
+        component: &'static str,
+        expected: String,
+        found: String,
+    },
+    /// A page cursor couldn't be parsed or belongs to another query or index state
+    InvalidCursor,
+    /// Indexes built with different settings can't be combined
+    SettingsMismatch(&'static str),
 }
 
 impl From<bincode::Error> for Error {
@@ -23,10 +44,44 @@ impl From<FromUtf8Error> for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Bincode(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Error::UTF8Error => write!(f, "invalid UTF-8"),
+            Error::Bincode(err) => write!(f, "bincode error: {err}"),
+            Error::Io(err) => write!(f, "IO error: {err}"),
+            Error::Corrupt(msg) => write!(f, "corrupt index data: {msg}"),
+            Error::VersionMismatch { expected, found } => {
+                write!(f, "unsupported format version {found}, expected {expected}")
+            }
+            Error::MissingComponent(name) => write!(f, "missing index component: {name}"),
+            Error::TypeMismatch {
+                component,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{component} type mismatch: expected {expected}, found {found}"
+            ),
+            Error::InvalidCursor => write!(f, "invalid page cursor"),
+            Error::SettingsMismatch(name) => write!(f, "indexes use different {name} settings"),
+        }
     }
 }
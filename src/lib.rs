@@ -1,10 +1,14 @@
+pub mod analysis;
 pub mod backend;
 pub mod error;
 pub mod retrieve;
+pub mod segmented;
+pub mod stats;
 pub mod traits;
 pub mod utils;
 
 use crate::traits::backend::Backend;
+use error::Error;
 use retrieve::Retrieve;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -12,7 +16,7 @@ use std::{
     ops::{Deref, DerefMut},
     path::Path,
 };
-use traits::{deser::DeSer, dict_item::DictItem};
+use traits::{deser::DeSer, dict_item::DictItem, kind::Kind};
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct Index<B, T, S> {
@@ -36,9 +40,15 @@ where
         }
     }
 
+    /// Opens an index from the given file
     #[inline]
-    pub fn open<P: AsRef<Path>>(&self, path: P) -> Option<Self> {
-        Some(Self::new(B::open(path)?))
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error>
+    where
+        B::Dict: Kind,
+        B::Postings: Kind,
+        B::Storage: Kind,
+    {
+        Ok(Self::new(B::open(path)?))
     }
 }
 
@@ -1,21 +1,42 @@
+pub mod page;
+pub mod query;
+pub mod result;
 pub mod retriever;
 
-use std::marker::PhantomData;
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
 
-use crate::traits::{
-    backend::Backend, deser::DeSer, dict_item::DictItem, dictionary::IndexDictionary,
+use crate::{
+    analysis::Analyzer,
+    error::Error,
+    traits::{
+        backend::Backend,
+        deser::DeSer,
+        dict_item::DictItem,
+        dictionary::{IndexDictionary, NGramDictionary},
+        storage::IndexStorage,
+    },
 };
 
-use retriever::Retriever;
+use page::{Page, PageCursor};
+use query::Query;
+use result::RetrieveResult;
+use retriever::{ResultRetriever, Retriever};
 
 /// Retrieves stuff from an index
 #[derive(Clone)]
 pub struct Retrieve<'a, B, T, S> {
     backend: &'a B,
     limit: usize,
+    offset: usize,
     unique: bool,
+    slop: u32,
     terms: Vec<u32>,
     posting_ids: Vec<u32>,
+    query: Option<Query<T>>,
+    term_dists: HashMap<u32, u32>,
+    /// Amount of distinct n-grams of the query, including those not in the dictionary. Reset by
+    /// all methods changing the terms
+    ngram_count: Option<usize>,
     p: PhantomData<T>,
     p2: PhantomData<S>,
 }
@@ -31,9 +52,14 @@ where
         Self {
             backend,
             unique: false,
+            slop: 0,
             limit: 0,
+            offset: 0,
             terms: vec![],
             posting_ids: vec![0],
+            query: None,
+            term_dists: HashMap::new(),
+            ngram_count: None,
             p: PhantomData,
             p2: PhantomData,
         }
@@ -58,6 +84,36 @@ where
         self.get::<R>().collect()
     }
 
+    /// Collects all items along with their IDs and match information
+    #[inline]
+    pub fn get_results<R>(self) -> Vec<RetrieveResult<S>>
+    where
+        R: ResultRetriever<'a, B, T, S>,
+    {
+        self.get::<R>().results().collect()
+    }
+
+    /// Collects a page of at most `limit` items and a cursor to the following page. Without a
+    /// limit, all items are returned in a single page
+    pub fn get_page<R>(mut self) -> Page<R::Output>
+    where
+        R: Retriever<'a, B, T, S>,
+    {
+        let (offset, limit) = (self.offset, self.limit);
+        let tag = self.fingerprint();
+        if limit > 0 {
+            // Fetch one more item to know whether there is another page
+            self.limit += 1;
+        }
+
+        let mut items: Vec<_> = self.get::<R>().collect();
+        let next = (limit > 0 && items.len() > limit).then(|| {
+            items.truncate(limit);
+            PageCursor::new(offset + limit, limit, tag)
+        });
+        Page { items, next }
+    }
+
     #[inline]
     pub fn unique(mut self) -> Self {
         self.unique = true;
@@ -70,6 +126,93 @@ where
         self
     }
 
+    /// Sets the maximum amount of additional terms allowed between the terms of a phrase
+    #[inline]
+    pub fn with_slop(mut self, slop: u32) -> Self {
+        self.slop = slop;
+        self
+    }
+
+    /// Returns the maximum amount of results. 0 means no limit
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Skips the first `offset` results
+    #[inline]
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Continues at the position of a cursor returned by `get_page`, using the same limit as the
+    /// page the cursor was created for. Has to be called after setting the query. Returns
+    /// `Error::InvalidCursor` if the cursor was created for another query or the index changed
+    /// since
+    pub fn with_cursor(self, cursor: PageCursor) -> Result<Self, Error> {
+        if cursor.limit() == 0 || cursor.tag() != self.fingerprint() {
+            return Err(Error::InvalidCursor);
+        }
+        Ok(self.with_offset(cursor.offset()).with_limit(cursor.limit()))
+    }
+
+    /// Returns a fingerprint of the query and the state of the index, without offset and limit
+    fn fingerprint(&self) -> u32 {
+        let mut state = crc32fast::Hasher::new();
+        self.terms.hash(&mut state);
+        self.posting_ids.hash(&mut state);
+        (self.unique, self.slop, self.ngram_count).hash(&mut state);
+        let mut term_dists: Vec<_> = self.term_dists.iter().collect();
+        term_dists.sort_unstable();
+        term_dists.hash(&mut state);
+        if let Some(query) = &self.query {
+            query.hash_into(&mut state);
+        }
+
+        // Adding and deleting items changes the statistics and compacting the storage length
+        let backend = self.backend;
+        (backend.dict().len(), backend.storage().len()).hash(&mut state);
+        if let Some(stats) = backend.stats() {
+            for p_id in 0..stats.len() as u32 {
+                let posting = stats.posting(p_id).unwrap();
+                (posting.item_count(), posting.total_len()).hash(&mut state);
+            }
+        }
+        state.finalize()
+    }
+
+    /// Returns the amount of top results a retriever has to collect to serve the requested
+    /// results, or 0 if all results are requested
+    #[inline]
+    pub(crate) fn window_len(&self) -> usize {
+        if self.limit == 0 {
+            return 0;
+        }
+        self.offset + self.limit
+    }
+
+    /// Resets what was derived from previously set terms
+    #[inline]
+    fn reset_term_state(&mut self) {
+        self.ngram_count = None;
+        self.term_dists.clear();
+    }
+
+    /// Drops all results before the offset and after the limit from the given ordered results
+    #[inline]
+    pub(crate) fn apply_window<I>(&self, items: &mut Vec<I>) {
+        items.drain(..self.offset.min(items.len()));
+        if self.limit > 0 {
+            items.truncate(self.limit);
+        }
+    }
+
     #[inline]
     pub fn all(mut self) -> Self {
         self.limit = 0;
@@ -81,6 +224,7 @@ where
     where
         U: Into<T>,
     {
+        self.reset_term_state();
         let id = self.backend.dict().get_id(term);
         if let Some(id) = id {
             self.terms = vec![id];
@@ -94,6 +238,7 @@ where
         I: IntoIterator<Item = U>,
         U: Into<T>,
     {
+        self.reset_term_state();
         self.terms = terms
             .into_iter()
             .filter_map(|i| self.backend.dict().get_id(i))
@@ -101,8 +246,95 @@ where
         self
     }
 
+    /// Sets the terms `analyzer` produces for the given query text. Should be the same analyzer
+    /// the items were indexed with
+    #[inline]
+    pub fn by_text(self, text: &str, analyzer: &Analyzer) -> Self
+    where
+        String: Into<T>,
+    {
+        self.by_terms(analyzer.analyze(text))
+    }
+
+    /// Sets the terms of a phrase in their order. If any of the terms is not in the dictionary,
+    /// the phrase can't match and no terms get set
+    #[inline]
+    pub fn by_phrase<I, U>(mut self, terms: I) -> Self
+    where
+        I: IntoIterator<Item = U>,
+        U: Into<T>,
+    {
+        let terms: Option<Vec<_>> = terms
+            .into_iter()
+            .map(|i| self.backend.dict().get_id(i))
+            .collect();
+        self.reset_term_state();
+        self.terms = terms.unwrap_or_default();
+        self
+    }
+
+    /// Sets all terms starting with the given prefix
+    #[inline]
+    pub fn by_prefix(mut self, prefix: &str) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.reset_term_state();
+        self.terms = self
+            .backend
+            .dict()
+            .terms_with_prefix(prefix)
+            .map(|(id, _)| id)
+            .collect();
+        self
+    }
+
+    /// Sets all terms within the edit distance `max_dist` of the given term
+    #[inline]
+    pub fn by_fuzzy_term(self, term: &str, max_dist: u32) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.by_fuzzy_terms([term], max_dist)
+    }
+
+    /// Expands each of the given terms to all terms within the edit distance `max_dist` and sets
+    /// them. The distances can be obtained with `term_distance`
+    pub fn by_fuzzy_terms<I, U>(mut self, terms: I, max_dist: u32) -> Self
+    where
+        I: IntoIterator<Item = U>,
+        U: AsRef<str>,
+        T: AsRef<str>,
+    {
+        self.reset_term_state();
+        self.terms.clear();
+
+        for term in terms {
+            let matches = self.backend.dict().fuzzy_terms(term.as_ref(), max_dist);
+            for (id, dist) in matches {
+                match self.term_dists.get_mut(&id) {
+                    Some(d) => *d = (*d).min(dist),
+                    None => {
+                        self.term_dists.insert(id, dist);
+                        self.terms.push(id);
+                    }
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Returns the edit distance of a term to the closest query term it was expanded from by
+    /// fuzzy matching. Terms set or added without fuzzy matching have a distance of 0
+    #[inline]
+    pub fn term_distance(&self, t_id: u32) -> u32 {
+        self.term_dists.get(&t_id).copied().unwrap_or(0)
+    }
+
     #[inline]
     pub fn by_term_id(mut self, t_id: u32) -> Self {
+        self.reset_term_state();
         if self.backend.dict().has_term_id(t_id) {
             self.terms.push(t_id);
         }
@@ -114,6 +346,7 @@ where
     where
         I: IntoIterator<Item = u32>,
     {
+        self.reset_term_state();
         self.terms = t_ids
             .into_iter()
             .filter(|i| self.backend.dict().has_term_id(*i))
@@ -126,8 +359,10 @@ where
     where
         U: Into<T>,
     {
+        self.ngram_count = None;
         let id = self.backend.dict().get_id(term);
         if let Some(id) = id {
+            self.term_dists.remove(&id);
             self.terms.push(id);
         }
         self
@@ -139,16 +374,20 @@ where
         I: IntoIterator<Item = U>,
         U: Into<T>,
     {
-        let iter = terms
-            .into_iter()
-            .filter_map(|i| self.backend.dict().get_id(i));
-        self.terms.extend(iter);
+        self.ngram_count = None;
+        let dict = self.backend.dict();
+        for id in terms.into_iter().filter_map(|i| dict.get_id(i)) {
+            self.term_dists.remove(&id);
+            self.terms.push(id);
+        }
         self
     }
 
     #[inline]
     pub fn add_term_id(mut self, t_id: u32) -> Self {
+        self.ngram_count = None;
         if self.backend.dict().has_term_id(t_id) {
+            self.term_dists.remove(&t_id);
             self.terms.push(t_id);
         }
         self
@@ -159,10 +398,22 @@ where
     where
         I: IntoIterator<Item = u32>,
     {
-        let iter = t_ids
-            .into_iter()
-            .filter(|i| self.backend.dict().has_term_id(*i));
-        self.terms.extend(iter);
+        self.ngram_count = None;
+        let dict = self.backend.dict();
+        for id in t_ids.into_iter().filter(|i| dict.has_term_id(*i)) {
+            self.term_dists.remove(&id);
+            self.terms.push(id);
+        }
+        self
+    }
+
+    /// Sets a boolean query tree. Only used by retrievers supporting queries
+    #[inline]
+    pub fn by_query<Q>(mut self, query: Q) -> Self
+    where
+        Q: Into<Query<T>>,
+    {
+        self.query = Some(query.into());
         self
     }
 
@@ -181,3 +432,29 @@ where
         self
     }
 }
+
+impl<'a, B, S> Retrieve<'a, B, String, S>
+where
+    S: DeSer,
+{
+    /// Sets the n-grams of the given query text. They get generated with the padding stored in
+    /// the indexes dictionary, so they match the n-grams the items were indexed with. N-grams
+    /// missing in the dictionary still count towards the query length used for similarities
+    pub fn by_ngrams(mut self, text: &str) -> Self
+    where
+        B: Backend<String, S>,
+        B::Dict: NGramDictionary,
+    {
+        self.reset_term_state();
+        let dict = self.backend.dict();
+        let mut ngrams = dict.ngrams(text);
+        ngrams.sort_unstable();
+        ngrams.dedup();
+        self.ngram_count = Some(ngrams.len());
+
+        let mut terms: Vec<u32> = ngrams.into_iter().filter_map(|i| dict.get_id(i)).collect();
+        terms.sort_unstable();
+        self.terms = terms;
+        self
+    }
+}
@@ -0,0 +1,73 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// A page of results returned by `Retrieve::get_page`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page<O> {
+    pub items: Vec<O>,
+    /// Cursor to retrieve the following page with. `None` if this is the last page
+    pub next: Option<PageCursor>,
+}
+
+/// Opaque position within the results of a query. Pass it to `Retrieve::with_cursor` to continue
+/// a search where the previous page ended. Cursors are only valid for the same query on an
+/// unchanged index, which `with_cursor` checks using a fingerprint of both. The string
+/// representation can be used to hand cursors to clients
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PageCursor {
+    offset: usize,
+    limit: usize,
+    /// Fingerprint of the query and index the cursor was created for
+    tag: u32,
+}
+
+impl PageCursor {
+    #[inline]
+    pub(crate) fn new(offset: usize, limit: usize, tag: u32) -> Self {
+        Self { offset, limit, tag }
+    }
+
+    #[inline]
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    #[inline]
+    pub(crate) fn limit(&self) -> usize {
+        self.limit
+    }
+
+    #[inline]
+    pub(crate) fn tag(&self) -> u32 {
+        self.tag
+    }
+}
+
+impl Display for PageCursor {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:x}.{:x}.{:x}", self.offset, self.limit, self.tag)
+    }
+}
+
+impl FromStr for PageCursor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.');
+        let mut next = || parts.next().ok_or(Error::InvalidCursor);
+        let parse = |i: &str| usize::from_str_radix(i, 16).map_err(|_| Error::InvalidCursor);
+        let (offset, limit) = (parse(next()?)?, parse(next()?)?);
+        let tag = u32::from_str_radix(next()?, 16).map_err(|_| Error::InvalidCursor)?;
+
+        // Pages always have a limit, otherwise there wouldn't be a following page
+        if limit == 0 || parts.next().is_some() {
+            return Err(Error::InvalidCursor);
+        }
+        Ok(Self::new(offset, limit, tag))
+    }
+}
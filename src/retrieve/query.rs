@@ -0,0 +1,304 @@
+use crate::{
+    backend::memory::postings::bitmap,
+    traits::{
+        backend::Backend, deser::DeSer, dict_item::DictItem, dictionary::IndexDictionary,
+        postings::IndexPostings, storage::IndexStorage,
+    },
+    utils::sorted,
+};
+use roaring::RoaringBitmap;
+use std::hash::Hasher;
+
+/// A node in a boolean query tree
+#[derive(Clone, Debug)]
+pub enum Query<T> {
+    /// Matches all items indexed with the given term
+    Term(T),
+    /// Matches all items indexed with the given term ID
+    TermId(u32),
+    /// Nested group of clauses
+    Bool(BoolQuery<T>),
+}
+
+/// Boolean query consisting of must (AND), should (OR) and must_not (NOT) clauses.
+///
+/// An item matches if it matches all `must` clauses, at least one `should` clause if there is no
+/// `must` clause, and none of the `must_not` clauses. A query without any `must` and `should`
+/// clauses matches all items in the storage, except for the excluded ones.
+#[derive(Clone, Debug)]
+pub struct BoolQuery<T> {
+    must: Vec<Query<T>>,
+    should: Vec<Query<T>>,
+    must_not: Vec<Query<T>>,
+}
+
+impl<T> BoolQuery<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            must: vec![],
+            should: vec![],
+            must_not: vec![],
+        }
+    }
+
+    /// Adds a clause all results have to match
+    #[inline]
+    pub fn must<Q: Into<Query<T>>>(mut self, q: Q) -> Self {
+        self.must.push(q.into());
+        self
+    }
+
+    /// Adds a clause of which at least one has to match if there are no `must` clauses
+    #[inline]
+    pub fn should<Q: Into<Query<T>>>(mut self, q: Q) -> Self {
+        self.should.push(q.into());
+        self
+    }
+
+    /// Adds a clause no result is allowed to match
+    #[inline]
+    pub fn must_not<Q: Into<Query<T>>>(mut self, q: Q) -> Self {
+        self.must_not.push(q.into());
+        self
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.must.is_empty() && self.should.is_empty() && self.must_not.is_empty()
+    }
+}
+
+impl<T: DeSer> Query<T> {
+    /// Feeds the structure and terms of the query into `state`. Terms are hashed by their
+    /// encoding, so they don't have to implement `Hash`
+    pub(crate) fn hash_into<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Query::Term(term) => {
+                state.write_u8(0);
+                state.write(&term.encode_vec().unwrap_or_default());
+            }
+            Query::TermId(id) => {
+                state.write_u8(1);
+                state.write_u32(*id);
+            }
+            Query::Bool(q) => {
+                state.write_u8(2);
+                for clauses in [&q.must, &q.should, &q.must_not] {
+                    state.write_usize(clauses.len());
+                    for clause in clauses {
+                        clause.hash_into(state);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for BoolQuery<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for Query<T> {
+    #[inline]
+    fn from(term: T) -> Self {
+        Self::Term(term)
+    }
+}
+
+impl<T> From<BoolQuery<T>> for Query<T> {
+    #[inline]
+    fn from(q: BoolQuery<T>) -> Self {
+        Self::Bool(q)
+    }
+}
+
+/// A set of item IDs on which boolean queries get evaluated
+pub trait PostingSet: Sized {
+    /// Creates a set containing all items from `0..len`
+    fn all(len: u32) -> Self;
+
+    fn union(self, other: &Self) -> Self;
+
+    fn intersect(self, other: &Self) -> Self;
+
+    fn difference(self, other: &Self) -> Self;
+
+    fn len(&self) -> u64;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Sorted list of item IDs
+impl PostingSet for Vec<u32> {
+    #[inline]
+    fn all(len: u32) -> Self {
+        (0..len).collect()
+    }
+
+    #[inline]
+    fn union(self, other: &Self) -> Self {
+        sorted::union([&self, other])
+    }
+
+    #[inline]
+    fn intersect(self, other: &Self) -> Self {
+        sorted::intersect(&self, other)
+    }
+
+    #[inline]
+    fn difference(self, other: &Self) -> Self {
+        sorted::difference(&self, other)
+    }
+
+    #[inline]
+    fn len(&self) -> u64 {
+        self.len() as u64
+    }
+}
+
+/// Evaluates queries directly on bitmaps
+impl PostingSet for RoaringBitmap {
+    #[inline]
+    fn all(len: u32) -> Self {
+        let mut bitmap = RoaringBitmap::new();
+        bitmap.insert_range(0..len);
+        bitmap
+    }
+
+    #[inline]
+    fn union(self, other: &Self) -> Self {
+        self | other
+    }
+
+    #[inline]
+    fn intersect(self, other: &Self) -> Self {
+        self & other
+    }
+
+    #[inline]
+    fn difference(self, other: &Self) -> Self {
+        self - other
+    }
+
+    #[inline]
+    fn len(&self) -> u64 {
+        RoaringBitmap::len(self)
+    }
+}
+
+/// Postings returning their lists as `PostingSet` of type `P`
+pub trait PostingSetSource<P: PostingSet> {
+    /// Returns the items of the postings-list with the given ID
+    fn posting_set(&self, id: u32) -> P;
+}
+
+impl<I: IndexPostings> PostingSetSource<Vec<u32>> for I {
+    #[inline]
+    fn posting_set(&self, id: u32) -> Vec<u32> {
+        self.get_posting(id)
+    }
+}
+
+impl PostingSetSource<RoaringBitmap> for bitmap::Postings {
+    #[inline]
+    fn posting_set(&self, id: u32) -> RoaringBitmap {
+        self.get_bitmap(id).cloned().unwrap_or_default()
+    }
+}
+
+impl<T> Query<T>
+where
+    T: DictItem + Clone,
+{
+    /// Evaluates the query and returns all matching items. `fetch` returns the items of a term
+    pub(crate) fn eval<B, S, P, F>(&self, backend: &B, fetch: &mut F) -> P
+    where
+        B: Backend<T, S>,
+        S: DeSer,
+        P: PostingSet,
+        F: FnMut(u32) -> P,
+    {
+        match self {
+            Query::Term(term) => match backend.dict().get_id(term.clone()) {
+                Some(t_id) => fetch(t_id),
+                None => P::all(0),
+            },
+            Query::TermId(t_id) => fetch(*t_id),
+            Query::Bool(b) => b.eval(backend, fetch),
+        }
+    }
+
+    /// Returns the IDs of all terms items can match on. Terms only used in `must_not` clauses
+    /// and terms missing in the dictionary are left out
+    pub(crate) fn term_ids<B, S>(&self, backend: &B) -> Vec<u32>
+    where
+        B: Backend<T, S>,
+        S: DeSer,
+    {
+        let mut out = vec![];
+        self.collect_term_ids(backend, &mut out);
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+
+    fn collect_term_ids<B, S>(&self, backend: &B, out: &mut Vec<u32>)
+    where
+        B: Backend<T, S>,
+        S: DeSer,
+    {
+        match self {
+            Query::Term(term) => out.extend(backend.dict().get_id(term.clone())),
+            Query::TermId(t_id) => out.push(*t_id),
+            Query::Bool(b) => {
+                for q in b.must.iter().chain(&b.should) {
+                    q.collect_term_ids(backend, out);
+                }
+            }
+        }
+    }
+}
+
+impl<T> BoolQuery<T>
+where
+    T: DictItem + Clone,
+{
+    pub(crate) fn eval<B, S, P, F>(&self, backend: &B, fetch: &mut F) -> P
+    where
+        B: Backend<T, S>,
+        S: DeSer,
+        P: PostingSet,
+        F: FnMut(u32) -> P,
+    {
+        let mut res = if !self.must.is_empty() {
+            let mut sets: Vec<P> = self.must.iter().map(|q| q.eval(backend, fetch)).collect();
+
+            // Start with the smallest set to keep intermediate results small
+            sets.sort_by_key(|i| i.len());
+
+            let mut sets = sets.into_iter();
+            let first = sets.next().unwrap();
+            sets.fold(first, |acc, i| acc.intersect(&i))
+        } else if !self.should.is_empty() {
+            let sets = self.should.iter().map(|q| q.eval(backend, fetch));
+            sets.fold(P::all(0), |acc, i| acc.union(&i))
+        } else {
+            P::all(backend.storage().len() as u32)
+        };
+
+        if !self.must_not.is_empty() && !res.is_empty() {
+            for q in &self.must_not {
+                res = res.difference(&q.eval(backend, fetch));
+            }
+        }
+
+        res
+    }
+}
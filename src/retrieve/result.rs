@@ -0,0 +1,78 @@
+use crate::traits::{
+    backend::Backend,
+    deser::DeSer,
+    dict_item::DictItem,
+    postings::{IndexPostings, PostingCursor},
+};
+
+/// A retrieved item along with the information how it matched the query
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetrieveResult<S> {
+    /// ID of the item in the indexes storage
+    pub item_id: u32,
+    pub item: S,
+    /// Query terms the item is indexed with
+    pub matches: Vec<TermMatch>,
+    /// Score of the item. Only set by scoring retrievers
+    pub score: Option<f32>,
+}
+
+/// A query term found in a postings list of an item
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TermMatch {
+    pub term_id: u32,
+    pub posting_id: u32,
+}
+
+impl<S> RetrieveResult<S> {
+    /// Returns the IDs of all matched terms in ascending order
+    pub fn term_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<_> = self.matches.iter().map(|i| i.term_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns the IDs of all postings the terms matched in, in ascending order
+    pub fn posting_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<_> = self.matches.iter().map(|i| i.posting_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+/// Returns all pairs of the given terms and postings whose postings list contains `item`. The
+/// lists are expected to be sorted by item ID, as the index builders create them
+pub(crate) fn term_matches<B, T, S>(
+    backend: &B,
+    terms: &[u32],
+    posting_ids: &[u32],
+    item: u32,
+) -> Vec<TermMatch>
+where
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    let mut matches = vec![];
+    for posting_id in posting_ids {
+        let postings = match backend.postings(*posting_id) {
+            Some(p) => p,
+            None => continue,
+        };
+
+        for term_id in terms {
+            if postings.cursor(*term_id).advance_to(item) == Some(item) {
+                matches.push(TermMatch {
+                    term_id: *term_id,
+                    posting_id: *posting_id,
+                });
+            }
+        }
+    }
+
+    matches.sort_unstable();
+    matches.dedup();
+    matches
+}
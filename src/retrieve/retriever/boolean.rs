@@ -0,0 +1,148 @@
+use super::{ResultRetriever, Retrieve, Retriever};
+use crate::{
+    retrieve::query::{BoolQuery, PostingSet, PostingSetSource, Query},
+    traits::{backend::Backend, deser::DeSer, dict_item::DictItem},
+};
+use roaring::RoaringBitmap;
+use std::marker::PhantomData;
+
+/// Retriever evaluating boolean queries set with `Retrieve::by_query`. If no query was set,
+/// all query terms have to match. Yields items in ascending order of their IDs.
+///
+/// All set operations are done on `P`, which has to be supported by the postings. Sorted lists
+/// work with all postings
+pub struct BoolRetriever<'a, B, T, S, P = Vec<u32>> {
+    retrieve: Retrieve<'a, B, T, S>,
+    item_ids: Vec<u32>,
+    pos: usize,
+    did_setup: bool,
+    set: PhantomData<P>,
+}
+
+/// `BoolRetriever` doing all set operations directly on the bitmaps of bitmap postings
+pub type BitmapBoolRetriever<'a, B, T, S> = BoolRetriever<'a, B, T, S, RoaringBitmap>;
+
+impl<'a, B, T, S, P> Retriever<'a, B, T, S> for BoolRetriever<'a, B, T, S, P>
+where
+    B: Backend<T, S>,
+    B::Postings: PostingSetSource<P>,
+    T: DictItem + Clone,
+    S: DeSer,
+    P: PostingSet + IntoIterator<Item = u32>,
+{
+    type Output = S;
+
+    #[inline]
+    fn new(retrieve: Retrieve<'a, B, T, S>) -> Self {
+        Self {
+            retrieve,
+            item_ids: vec![],
+            pos: 0,
+            did_setup: false,
+            set: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn q_term_ids(&self) -> &[u32] {
+        &self.retrieve.terms
+    }
+}
+
+impl<'a, B, T, S, P> ResultRetriever<'a, B, T, S> for BoolRetriever<'a, B, T, S, P>
+where
+    B: Backend<T, S>,
+    B::Postings: PostingSetSource<P>,
+    T: DictItem + Clone,
+    S: DeSer,
+    P: PostingSet + IntoIterator<Item = u32>,
+{
+    #[inline]
+    fn q_posting_ids(&self) -> &[u32] {
+        &self.retrieve.posting_ids
+    }
+
+    #[inline]
+    fn backend(&self) -> &B {
+        self.retrieve.backend
+    }
+
+    #[inline]
+    fn next_id(&mut self) -> Option<(u32, Option<f32>)> {
+        if !self.did_setup {
+            self.setup();
+        }
+
+        let item_id = *self.item_ids.get(self.pos)?;
+        self.pos += 1;
+        Some((item_id, None))
+    }
+}
+
+impl<'a, B, T, S, P> BoolRetriever<'a, B, T, S, P>
+where
+    B: Backend<T, S>,
+    B::Postings: PostingSetSource<P>,
+    T: DictItem + Clone,
+    S: DeSer,
+    P: PostingSet + IntoIterator<Item = u32>,
+{
+    fn setup(&mut self) {
+        self.did_setup = true;
+
+        let query = match take_query(&mut self.retrieve) {
+            Some(q) => q,
+            None => return,
+        };
+
+        let backend = self.retrieve.backend;
+        self.retrieve.terms = query.term_ids(backend);
+        let posting_ids = &self.retrieve.posting_ids;
+        let mut fetch = |t_id: u32| {
+            let sets = posting_ids
+                .iter()
+                .filter_map(|p_id| backend.postings(*p_id))
+                .map(|p| p.posting_set(t_id));
+            sets.fold(P::all(0), |acc, i| acc.union(&i))
+        };
+        let res: P = query.eval(backend, &mut fetch);
+
+        let limit = match self.retrieve.limit {
+            0 => usize::MAX,
+            limit => limit,
+        };
+        let res = res.into_iter().filter(|i| !backend.is_deleted(*i));
+        self.item_ids = res.skip(self.retrieve.offset).take(limit).collect();
+    }
+}
+
+/// Returns the query of the retrieve or a query requiring all query terms
+fn take_query<B, T, S>(retrieve: &mut Retrieve<B, T, S>) -> Option<Query<T>> {
+    if let Some(q) = retrieve.query.take() {
+        return Some(q);
+    }
+
+    if retrieve.terms.is_empty() {
+        return None;
+    }
+
+    let terms = retrieve.terms.iter();
+    let query = terms.fold(BoolQuery::new(), |q, t| q.must(Query::TermId(*t)));
+    Some(query.into())
+}
+
+impl<'a, B, T, S, P> Iterator for BoolRetriever<'a, B, T, S, P>
+where
+    B: Backend<T, S>,
+    B::Postings: PostingSetSource<P>,
+    T: DictItem + Clone,
+    S: DeSer,
+    P: PostingSet + IntoIterator<Item = u32>,
+{
+    type Item = S;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().map(|i| i.2)
+    }
+}
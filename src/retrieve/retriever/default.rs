@@ -1,16 +1,22 @@
-use super::{Retrieve, Retriever};
-use crate::traits::{
-    backend::Backend, deser::DeSer, dict_item::DictItem, postings::IndexPostings,
-    storage::IndexStorage,
-};
-use std::collections::HashSet;
-
-/// Default retriever algroithm. Optimized for normal retrievals
+use super::{ResultRetriever, Retrieve, Retriever};
+use crate::traits::{backend::Backend, deser::DeSer, dict_item::DictItem, postings::IndexPostings};
+use std::collections::{HashSet, VecDeque};
+
+/// Default retriever algroithm. Optimized for normal retrievals. Yields the items of each query
+/// term in the order the terms were given, postings by postings in the order of their postings
+/// lists
 pub struct DefaultRetrieve<'a, B, T, S> {
     retrieve: Retrieve<'a, B, T, S>,
 
     // Buffer for going over storage ids
-    storage_buf: Vec<u32>,
+    storage_buf: VecDeque<u32>,
+
+    // Position of the next query term to fetch
+    term_pos: usize,
+
+    // Amount of results left to skip and to return
+    skip: usize,
+    left: Option<usize>,
 
     seen: Option<HashSet<u32>>,
 }
@@ -26,9 +32,14 @@ where
     #[inline]
     fn new(retrieve: Retrieve<'a, B, T, S>) -> Self {
         let seen = retrieve.unique.then(HashSet::new);
+        let skip = retrieve.offset;
+        let left = (retrieve.limit > 0).then_some(retrieve.limit);
         Self {
             retrieve,
-            storage_buf: Vec::with_capacity(10),
+            storage_buf: VecDeque::with_capacity(10),
+            term_pos: 0,
+            skip,
+            left,
             seen,
         }
     }
@@ -39,19 +50,49 @@ where
     }
 }
 
-impl<'a, B, T, S> DefaultRetrieve<'a, B, T, S>
+impl<'a, B, T, S> ResultRetriever<'a, B, T, S> for DefaultRetrieve<'a, B, T, S>
 where
     B: Backend<T, S>,
-    T: DictItem + Ord,
+    T: DictItem,
     S: DeSer,
 {
+    #[inline]
+    fn q_posting_ids(&self) -> &[u32] {
+        &self.retrieve.posting_ids
+    }
+
     #[inline]
     fn backend(&self) -> &B {
         self.retrieve.backend
     }
 
     #[inline]
-    fn get_or_fill(&mut self) -> Option<&mut Vec<u32>> {
+    fn next_id(&mut self) -> Option<(u32, Option<f32>)> {
+        if self.left == Some(0) {
+            return None;
+        }
+
+        while self.skip > 0 {
+            self.get_or_fill()?.pop_front();
+            self.skip -= 1;
+        }
+
+        let next_id = self.get_or_fill()?.pop_front().expect("Hit bug");
+        if let Some(left) = &mut self.left {
+            *left -= 1;
+        }
+        Some((next_id, None))
+    }
+}
+
+impl<'a, B, T, S> DefaultRetrieve<'a, B, T, S>
+where
+    B: Backend<T, S>,
+    T: DictItem + Ord,
+    S: DeSer,
+{
+    #[inline]
+    fn get_or_fill(&mut self) -> Option<&mut VecDeque<u32>> {
         if self.storage_buf.is_empty() {
             self.fill_buff()?;
         }
@@ -68,21 +109,24 @@ where
         debug_assert!(self.storage_buf.is_empty());
 
         loop {
-            let t_id = self.retrieve.terms.pop()?;
+            let t_id = *self.retrieve.terms.get(self.term_pos)?;
+            self.term_pos += 1;
 
+            let backend = self.retrieve.backend;
             for post_id in &self.retrieve.posting_ids {
-                let postings = match self.backend().postings(*post_id) {
+                let postings = match backend.postings(*post_id) {
                     Some(p) => p,
                     None => continue,
                 };
-                let iter =
-                    postings
-                        .get_posting(t_id)
-                        .into_iter()
-                        .filter(|i| match &mut self.seen {
-                            Some(v) => v.insert(*i),
-                            None => true,
-                        });
+                let iter = postings.cursor(t_id).filter(|i| {
+                    if backend.is_deleted(*i) {
+                        return false;
+                    }
+                    match &mut self.seen {
+                        Some(v) => v.insert(*i),
+                        None => true,
+                    }
+                });
                 self.storage_buf.extend(iter);
             }
 
@@ -106,12 +150,6 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let next_id = self.get_or_fill()?.pop().expect("Hit bug");
-        let item = self
-            .backend()
-            .storage()
-            .get_item(next_id)
-            .expect("Invalid index");
-        Some(item)
+        self.next_item().map(|i| i.2)
     }
 }
@@ -1,9 +1,20 @@
+pub mod boolean;
 pub mod default;
 pub mod ngram;
+pub mod phrase;
+pub mod scored;
 
-use crate::traits::{backend::Backend, deser::DeSer};
+use crate::traits::{backend::Backend, deser::DeSer, storage::IndexStorage};
+use order_struct::OrderBy;
+use std::{
+    collections::{BinaryHeap, HashMap},
+    marker::PhantomData,
+};
 
-use super::Retrieve;
+use super::{
+    result::{term_matches, RetrieveResult},
+    Retrieve,
+};
 
 /// Allow custom retrieve algorithms for `Retrieve`
 pub trait Retriever<'a, B, T, S>: Iterator<Item = Self::Output>
@@ -21,3 +32,113 @@ where
     /// Returns the term_ids of the query
     fn q_term_ids(&self) -> &[u32];
 }
+
+/// Retrievers that can return the IDs, scores and matched terms of their items along with the
+/// items. Implemented by all built-in retrievers
+pub trait ResultRetriever<'a, B, T, S>: Retriever<'a, B, T, S>
+where
+    B: Backend<T, S>,
+    T: DeSer + Ord,
+    S: DeSer,
+{
+    /// Returns the IDs of the postings the query gets searched in
+    fn q_posting_ids(&self) -> &[u32];
+
+    /// Returns the backend the retriever searches in
+    fn backend(&self) -> &B;
+
+    /// Returns the ID and, for scoring retrievers, the score of the next matching item without
+    /// loading the item from the storage. Advances the same position as `next`
+    fn next_id(&mut self) -> Option<(u32, Option<f32>)>;
+
+    /// Returns the ID, score and item of the next matching item. Items that can't be loaded from
+    /// the storage, e.g. because they fail to decode, get skipped
+    fn next_item(&mut self) -> Option<(u32, Option<f32>, S)> {
+        loop {
+            let (item_id, score) = self.next_id()?;
+            if let Some(item) = self.backend().storage().get_item(item_id) {
+                return Some((item_id, score, item));
+            }
+        }
+    }
+
+    /// Returns the next matching item along with its ID, score and the query terms it matched.
+    /// Advances the same position as `next`
+    fn next_result(&mut self) -> Option<RetrieveResult<S>> {
+        let (item_id, score, item) = self.next_item()?;
+        let backend = self.backend();
+        let matches = term_matches(backend, self.q_term_ids(), self.q_posting_ids(), item_id);
+        Some(RetrieveResult {
+            item_id,
+            item,
+            matches,
+            score,
+        })
+    }
+
+    /// Turns the retriever into an iterator over `RetrieveResult`s
+    #[inline]
+    fn results(self) -> Results<Self, B, T, S>
+    where
+        Self: Sized,
+    {
+        Results {
+            retriever: self,
+            p: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the `RetrieveResult`s of a retriever
+pub struct Results<R, B, T, S> {
+    retriever: R,
+    p: PhantomData<(B, T, S)>,
+}
+
+impl<R, B, T, S> Results<R, B, T, S> {
+    /// Returns the underlying retriever
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.retriever
+    }
+}
+
+impl<'a, R, B, T, S> Iterator for Results<R, B, T, S>
+where
+    R: ResultRetriever<'a, B, T, S>,
+    B: Backend<T, S> + 'a,
+    T: DeSer + Ord,
+    S: DeSer,
+{
+    type Item = RetrieveResult<S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.retriever.next_result()
+    }
+}
+
+/// Returns the `k` highest scored items, lowest score first. Returns all items if `k` is 0
+pub(crate) fn top_k(scores: HashMap<u32, f32>, k: usize) -> Vec<(u32, f32)> {
+    let mut bin_heap = BinaryHeap::with_capacity(scores.len());
+    for (id, score) in scores {
+        bin_heap.push(OrderBy::new(
+            (id, score),
+            |a: &(u32, f32), b: &(u32, f32)| {
+                // Keep a persistent order for equal scores
+                a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0).reverse())
+            },
+        ));
+    }
+
+    let mut len = bin_heap.len();
+    if k > 0 {
+        len = len.min(k);
+    }
+
+    let mut out: Vec<_> = (0..len)
+        .map(|_| bin_heap.pop().unwrap().into_inner())
+        .collect();
+    out.reverse();
+    out
+}
@@ -1,22 +1,53 @@
-use super::Retriever;
+use super::{top_k, ResultRetriever, Retriever};
 use crate::{
     retrieve::Retrieve,
-    traits::{
-        backend::Backend, deser::DeSer, dict_item::DictItem, postings::IndexPostings,
-        storage::IndexStorage,
-    },
-};
-use intersect_iter::Intersect;
-use order_struct::OrderBy;
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap, HashSet},
+    traits::{backend::Backend, deser::DeSer, dict_item::DictItem, postings::IndexPostings},
 };
+use std::collections::HashMap;
+
+/// Similarity measures between the n-grams of a query and an item
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Similarity {
+    /// `shared / (query + item - shared)`
+    #[default]
+    Jaccard,
+    /// `2 * shared / (query + item)`
+    Dice,
+    /// `shared / sqrt(query * item)`
+    Cosine,
+    /// `shared / min(query, item)`
+    Overlap,
+}
 
-/// NGram optimized retriever
+impl Similarity {
+    /// Calculates the similarity from the amount of `shared` n-grams and the n-gram counts of the
+    /// query and the item. Returns a value between 0 and 1
+    pub fn score(&self, shared: u32, query_len: u32, item_len: u32) -> f32 {
+        let (shared, q, i) = (shared as f32, query_len as f32, item_len as f32);
+        let (num, denom) = match self {
+            Similarity::Jaccard => (shared, q + i - shared),
+            Similarity::Dice => (2.0 * shared, q + i),
+            Similarity::Cosine => (shared, (q * i).sqrt()),
+            Similarity::Overlap => (shared, q.min(i)),
+        };
+
+        if denom <= 0.0 {
+            return 0.0;
+        }
+        num / denom
+    }
+}
+
+/// NGram optimized retriever yielding `(similarity, item)` pairs ordered by descending
+/// similarity. Item n-gram counts are taken from the index statistics, the query n-gram count
+/// from `Retrieve::by_ngrams` or the amount of query terms. `Retrieve::with_limit` sets the
+/// amount of top results to return
 pub struct NGramRetriever<'a, const N: usize, B, T, S> {
     retrieve: Retrieve<'a, B, T, S>,
-    item_ids: Vec<u32>,
+    similarity: Similarity,
+    threshold: f32,
+    // Sorted ascending by score so results can be popped
+    results: Vec<(u32, f32)>,
     did_setup: bool,
 }
 
@@ -26,14 +57,17 @@ where
     T: DictItem,
     S: DeSer,
 {
-    type Output = S;
+    type Output = (f32, S);
 
     #[inline]
     fn new(mut retr: Retrieve<'a, B, T, S>) -> Self {
         retr.terms.sort_unstable();
+        retr.terms.dedup();
         Self {
             retrieve: retr,
-            item_ids: vec![],
+            similarity: Similarity::default(),
+            threshold: 0.0,
+            results: vec![],
             did_setup: false,
         }
     }
@@ -44,101 +78,125 @@ where
     }
 }
 
-impl<'a, const N: usize, B, T, S> NGramRetriever<'a, N, B, T, S>
+impl<'a, const N: usize, B, T, S> ResultRetriever<'a, B, T, S> for NGramRetriever<'a, N, B, T, S>
 where
     B: Backend<T, S>,
     T: DictItem,
     S: DeSer,
 {
-    /// Loads all required stuff for the iterator. Returns `None` if there is no such
-    fn setup(&mut self) -> Option<()> {
-        self.did_setup = true;
-
-        // Posting lists for all input terms
-        let term_posts = self.make_terms_posings();
-
-        // Map of StorageItemID -> MatchingTermCount
-        let itm_post_freqs = Self::calc_post_freqs(&term_posts);
-
-        // Storage Item ids
-        self.item_ids = Self::max_n(itm_post_freqs, self.retrieve.limit);
-
-        // Return None if empty
-        (!self.item_ids.is_empty()).then(|| ())?;
-
-        Some(())
+    #[inline]
+    fn q_posting_ids(&self) -> &[u32] {
+        &self.retrieve.posting_ids
     }
 
     #[inline]
-    fn make_terms_posings(&self) -> Vec<Vec<u32>> {
-        self.retrieve
-            .terms
-            .iter()
-            .flat_map(|i| {
-                self.retrieve.posting_ids.iter().filter_map(|pid| {
-                    let postings = self.backend().postings(*pid)?.get_posting(*i);
-                    (!postings.is_empty()).then(|| postings)
-                })
-            })
-            .collect()
+    fn backend(&self) -> &B {
+        self.retrieve.backend
     }
 
-    fn calc_post_freqs(term_posts: &[Vec<u32>]) -> HashMap<u32, u32> {
-        let mut id_count: HashMap<u32, u32> = HashMap::new();
+    #[inline]
+    fn next_id(&mut self) -> Option<(u32, Option<f32>)> {
+        if !self.did_setup {
+            self.setup();
+        }
 
-        let mut seen: HashSet<u32> = HashSet::new();
-        let mut added = vec![];
+        let (item_id, score) = self.results.pop()?;
+        Some((item_id, Some(score)))
+    }
+}
 
-        for (pos, postings) in term_posts.iter().enumerate() {
-            for i in postings.iter() {
-                if !seen.contains(i) {
-                    id_count.insert(*i, 1);
-                    added.push(*i);
-                }
-            }
+impl<'a, const N: usize, B, T, S> NGramRetriever<'a, N, B, T, S>
+where
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    /// Sets the similarity measure. Has to be called before the first item is retrieved
+    #[inline]
+    pub fn with_similarity(mut self, similarity: Similarity) -> Self {
+        self.similarity = similarity;
+        self
+    }
 
-            for list in term_posts.iter().skip(pos + 1) {
-                let lsi = Intersect::new(postings.iter(), list.iter());
-                for i in lsi.filter(|i| !seen.contains(*i)) {
-                    *id_count.entry(*i).or_default() += 1;
-                }
-            }
+    /// Drops all items with a similarity below `threshold`. Has to be called before the first
+    /// item is retrieved
+    #[inline]
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
 
-            seen.extend(added.drain(..));
-        }
+    fn setup(&mut self) {
+        self.did_setup = true;
+        let scores = self.calc_scores();
+        self.results = top_k(scores, self.retrieve.window_len());
 
-        id_count
+        // The highest scored results come last
+        let len = self.results.len().saturating_sub(self.retrieve.offset);
+        self.results.truncate(len);
     }
 
-    /// Gets first n HashMap keys by highest map-value first
-    fn max_n(inp: HashMap<u32, u32>, n: usize) -> Vec<u32> {
-        let mut bin_heap = BinaryHeap::with_capacity(inp.len());
-        for (k, v) in inp.iter() {
-            bin_heap.push(OrderBy::new((*k, *v), |a, b| {
-                let cmp = a.1.cmp(&b.1).reverse();
+    /// Calculates the similarity of all items sharing at least one n-gram with the query
+    fn calc_scores(&self) -> HashMap<u32, f32> {
+        let backend = self.backend();
+        let posting_ids = &self.retrieve.posting_ids;
+        let query_len = self
+            .retrieve
+            .ngram_count
+            .unwrap_or(self.retrieve.terms.len());
+        let query_len = (query_len * posting_ids.len()) as u32;
+
+        let item_len = |item: u32| -> u32 {
+            let stats = match backend.stats() {
+                Some(s) => s,
+                None => return 0,
+            };
+            posting_ids
+                .iter()
+                .filter_map(|pid| stats.posting(*pid))
+                .map(|i| i.item_len(item))
+                .sum()
+        };
+
+        self.calc_post_freqs()
+            .into_iter()
+            .filter_map(|(item, shared)| {
+                // Without stats, items are assumed to only consist of the shared n-grams
+                let item_len = item_len(item).max(shared);
+                let score = self
+                    .similarity
+                    .score(shared, query_len.max(shared), item_len);
+                (score >= self.threshold).then_some((item, score))
+            })
+            .collect()
+    }
 
-                // keep a persistent order
-                if cmp == Ordering::Equal {
-                    return a.0.cmp(&b.0).reverse();
+    /// Counts for each item the amount of postings-lists of the query terms it occurs in
+    fn calc_post_freqs(&self) -> HashMap<u32, u32> {
+        // StorageItemID -> (MatchingTermCount, last counted list)
+        let mut id_count: HashMap<u32, (u32, usize)> = HashMap::new();
+
+        let lists = self.retrieve.terms.iter().flat_map(|t_id| {
+            self.retrieve
+                .posting_ids
+                .iter()
+                .filter_map(move |pid| Some(self.backend().postings(*pid)?.cursor(*t_id)))
+        });
+
+        for (list, cursor) in lists.enumerate() {
+            for item in cursor {
+                if self.backend().is_deleted(item) {
+                    continue;
                 }
-
-                cmp.reverse()
-            }));
-        }
-
-        let mut len = bin_heap.len();
-        if n > 0 {
-            len = len.min(n);
+                let entry = id_count.entry(item).or_insert((0, usize::MAX));
+                // Don't count items twice within the same list
+                if entry.1 != list {
+                    *entry = (entry.0 + 1, list);
+                }
+            }
         }
-        let mut vec = Vec::with_capacity(len);
-        vec.extend((0..len).map(|_| bin_heap.pop().unwrap().into_inner().0));
 
-        vec
-    }
-
-    #[inline]
-    fn backend(&self) -> &B {
-        self.retrieve.backend
+        id_count.into_iter().map(|(k, v)| (k, v.0)).collect()
     }
 }
 
@@ -148,15 +206,11 @@ where
     T: DictItem,
     S: DeSer,
 {
-    type Item = S;
+    type Item = (f32, S);
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if !self.did_setup {
-            self.setup()?;
-        }
-
-        let item_id = self.item_ids.pop()?;
-        Some(self.backend().storage().get_item(item_id).unwrap())
+        let (_, score, item) = self.next_item()?;
+        Some((score.unwrap_or_default(), item))
     }
 }
@@ -0,0 +1,160 @@
+use super::{ResultRetriever, Retrieve, Retriever};
+use crate::traits::{
+    backend::Backend, deser::DeSer, dict_item::DictItem, postings::IndexPositions,
+};
+use std::collections::HashMap;
+
+/// Retriever matching the query terms as phrase. Requires positional postings. The terms have
+/// to occur in the query's order with at most `Retrieve::with_slop` other terms in between.
+/// Yields items in ascending order of their IDs
+pub struct PhraseRetriever<'a, B, T, S> {
+    retrieve: Retrieve<'a, B, T, S>,
+    item_ids: Vec<u32>,
+    pos: usize,
+    did_setup: bool,
+}
+
+impl<'a, B, T, S> Retriever<'a, B, T, S> for PhraseRetriever<'a, B, T, S>
+where
+    B: Backend<T, S>,
+    B::Postings: IndexPositions,
+    T: DictItem,
+    S: DeSer,
+{
+    type Output = S;
+
+    #[inline]
+    fn new(retrieve: Retrieve<'a, B, T, S>) -> Self {
+        Self {
+            retrieve,
+            item_ids: vec![],
+            pos: 0,
+            did_setup: false,
+        }
+    }
+
+    #[inline]
+    fn q_term_ids(&self) -> &[u32] {
+        &self.retrieve.terms
+    }
+}
+
+impl<'a, B, T, S> ResultRetriever<'a, B, T, S> for PhraseRetriever<'a, B, T, S>
+where
+    B: Backend<T, S>,
+    B::Postings: IndexPositions,
+    T: DictItem,
+    S: DeSer,
+{
+    #[inline]
+    fn q_posting_ids(&self) -> &[u32] {
+        &self.retrieve.posting_ids
+    }
+
+    #[inline]
+    fn backend(&self) -> &B {
+        self.retrieve.backend
+    }
+
+    #[inline]
+    fn next_id(&mut self) -> Option<(u32, Option<f32>)> {
+        if !self.did_setup {
+            self.setup();
+        }
+
+        let item_id = *self.item_ids.get(self.pos)?;
+        self.pos += 1;
+        Some((item_id, None))
+    }
+}
+
+impl<'a, B, T, S> PhraseRetriever<'a, B, T, S>
+where
+    B: Backend<T, S>,
+    B::Postings: IndexPositions,
+    T: DictItem,
+    S: DeSer,
+{
+    fn setup(&mut self) {
+        self.did_setup = true;
+
+        if self.retrieve.terms.is_empty() {
+            return;
+        }
+
+        for post_id in &self.retrieve.posting_ids {
+            let postings = match self.retrieve.backend.postings(*post_id) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            // item -> positions of each phrase term
+            let mut items: HashMap<u32, Vec<Vec<u32>>> = HashMap::new();
+            for (i, t_id) in self.retrieve.terms.iter().enumerate() {
+                for (item, pos) in postings.get_positions(*t_id) {
+                    let entry = items.entry(item).or_default();
+                    // Only keep items containing all previous terms
+                    if entry.len() == i {
+                        entry.push(pos);
+                    }
+                }
+            }
+
+            let term_count = self.retrieve.terms.len();
+            let slop = self.retrieve.slop;
+            let matches = items
+                .into_iter()
+                .filter(|(item, _)| !self.retrieve.backend.is_deleted(*item))
+                .filter(|(_, pos)| pos.len() == term_count && Self::is_match(pos, slop))
+                .map(|i| i.0);
+            self.item_ids.extend(matches);
+        }
+
+        self.item_ids.sort_unstable();
+        self.item_ids.dedup();
+        self.retrieve.apply_window(&mut self.item_ids);
+    }
+
+    /// Returns `true` if the given term positions contain the phrase with
+    /// at most `slop` additional terms in between
+    fn is_match(positions: &[Vec<u32>], slop: u32) -> bool {
+        'outer: for start in &positions[0] {
+            let mut prev = *start;
+            let mut gaps = 0;
+
+            for term_pos in &positions[1..] {
+                // Choosing the closest following position keeps the gaps minimal
+                let next = term_pos.partition_point(|i| *i <= prev);
+                let pos = match term_pos.get(next) {
+                    Some(p) => *p,
+                    None => continue 'outer,
+                };
+
+                gaps += pos - prev - 1;
+                if gaps > slop {
+                    continue 'outer;
+                }
+                prev = pos;
+            }
+
+            return true;
+        }
+
+        false
+    }
+}
+
+impl<'a, B, T, S> Iterator for PhraseRetriever<'a, B, T, S>
+where
+    B: Backend<T, S>,
+    B::Postings: IndexPositions,
+    T: DictItem,
+    S: DeSer,
+{
+    type Item = S;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().map(|i| i.2)
+    }
+}
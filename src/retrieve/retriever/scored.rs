@@ -0,0 +1,210 @@
+use super::{top_k, ResultRetriever, Retrieve, Retriever};
+use crate::traits::{
+    backend::Backend, deser::DeSer, dict_item::DictItem, postings::IndexPostings,
+    storage::IndexStorage,
+};
+use std::collections::HashMap;
+
+/// Ranks matching items by BM25
+pub type Bm25Retriever<'a, B, T, S> = ScoredRetriever<'a, Bm25, B, T, S>;
+
+/// Ranks matching items by TF-IDF
+pub type TfIdfRetriever<'a, B, T, S> = ScoredRetriever<'a, TfIdf, B, T, S>;
+
+/// A relevance function used by `ScoredRetriever`
+pub trait ScoreModel: Default {
+    /// Scores a single term for an item.
+    ///
+    /// `tf`: Amount of occurrences of the term in the item
+    /// `df`: Amount of items containing the term
+    /// `item_count`: Amount of items in the postings
+    /// `item_len`: Amount of terms the item was indexed with
+    /// `avg_len`: Average amount of terms per item
+    fn score(&self, tf: u32, df: u32, item_count: u32, item_len: u32, avg_len: f32) -> f32;
+}
+
+/// Okapi BM25
+pub struct Bm25 {
+    pub k1: f32,
+    pub b: f32,
+}
+
+impl Default for Bm25 {
+    #[inline]
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+impl ScoreModel for Bm25 {
+    #[inline]
+    fn score(&self, tf: u32, df: u32, item_count: u32, item_len: u32, avg_len: f32) -> f32 {
+        let (tf, df, n) = (tf as f32, df as f32, item_count.max(df) as f32);
+        let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+
+        let len_norm = if avg_len > 0.0 {
+            1.0 - self.b + self.b * (item_len as f32 / avg_len)
+        } else {
+            1.0
+        };
+
+        idf * (tf * (self.k1 + 1.0)) / (tf + self.k1 * len_norm)
+    }
+}
+
+/// TF-IDF with logarithmic term frequency and item length normalization
+#[derive(Default)]
+pub struct TfIdf;
+
+impl ScoreModel for TfIdf {
+    #[inline]
+    fn score(&self, tf: u32, df: u32, item_count: u32, item_len: u32, _avg_len: f32) -> f32 {
+        let (df, n) = (df as f32, item_count.max(df) as f32);
+        let tf = 1.0 + (tf as f32).ln();
+        let idf = ((1.0 + n) / (1.0 + df)).ln() + 1.0;
+        let norm = (item_len.max(1) as f32).sqrt();
+        tf * idf / norm
+    }
+}
+
+/// Retriever yielding `(score, item)` pairs ordered by descending score. `Retrieve::with_limit`
+/// sets the amount of top results to return. Item lengths are taken from the index statistics;
+/// without them no length normalization is applied. Terms expanded by fuzzy matching are weighted
+/// by `1 / (1 + distance)`.
+pub struct ScoredRetriever<'a, M, B, T, S> {
+    retrieve: Retrieve<'a, B, T, S>,
+    model: M,
+    // Sorted ascending by score so results can be popped
+    results: Vec<(u32, f32)>,
+    did_setup: bool,
+}
+
+impl<'a, M, B, T, S> Retriever<'a, B, T, S> for ScoredRetriever<'a, M, B, T, S>
+where
+    M: ScoreModel,
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    type Output = (f32, S);
+
+    #[inline]
+    fn new(retrieve: Retrieve<'a, B, T, S>) -> Self {
+        Self {
+            retrieve,
+            model: M::default(),
+            results: vec![],
+            did_setup: false,
+        }
+    }
+
+    #[inline]
+    fn q_term_ids(&self) -> &[u32] {
+        &self.retrieve.terms
+    }
+}
+
+impl<'a, M, B, T, S> ResultRetriever<'a, B, T, S> for ScoredRetriever<'a, M, B, T, S>
+where
+    M: ScoreModel,
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    #[inline]
+    fn q_posting_ids(&self) -> &[u32] {
+        &self.retrieve.posting_ids
+    }
+
+    #[inline]
+    fn backend(&self) -> &B {
+        self.retrieve.backend
+    }
+
+    #[inline]
+    fn next_id(&mut self) -> Option<(u32, Option<f32>)> {
+        if !self.did_setup {
+            self.setup();
+        }
+
+        let (item_id, score) = self.results.pop()?;
+        Some((item_id, Some(score)))
+    }
+}
+
+impl<'a, M, B, T, S> ScoredRetriever<'a, M, B, T, S>
+where
+    M: ScoreModel,
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    /// Sets the score model. Has to be called before the first item is retrieved
+    #[inline]
+    pub fn with_model(mut self, model: M) -> Self {
+        self.model = model;
+        self
+    }
+
+    fn setup(&mut self) {
+        self.did_setup = true;
+        let scores = self.calc_scores();
+        self.results = top_k(scores, self.retrieve.window_len());
+
+        // The highest scored results come last
+        let len = self.results.len().saturating_sub(self.retrieve.offset);
+        self.results.truncate(len);
+    }
+
+    /// Calculates the scores of all items matching at least one of the query terms
+    fn calc_scores(&self) -> HashMap<u32, f32> {
+        let backend = self.retrieve.backend;
+        let mut scores: HashMap<u32, f32> = HashMap::new();
+
+        for post_id in &self.retrieve.posting_ids {
+            let postings = match backend.postings(*post_id) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            let stats = backend.stats().and_then(|i| i.posting(*post_id));
+            let item_count = stats
+                .map(|i| i.item_count())
+                .unwrap_or_else(|| backend.storage().len() as u32);
+            let avg_len = stats.map(|i| i.avg_len()).unwrap_or(0.0);
+
+            for t_id in &self.retrieve.terms {
+                let tfs = postings.get_term_freqs(*t_id);
+                let df = tfs.len() as u32;
+                let weight = 1.0 / (1 + self.retrieve.term_distance(*t_id)) as f32;
+
+                for (item, tf) in tfs {
+                    if backend.is_deleted(item) {
+                        continue;
+                    }
+                    let item_len = stats.map(|i| i.item_len(item)).unwrap_or(0);
+                    let score = self.model.score(tf, df, item_count, item_len, avg_len);
+                    *scores.entry(item).or_default() += score * weight;
+                }
+            }
+        }
+
+        scores
+    }
+}
+
+impl<'a, M, B, T, S> Iterator for ScoredRetriever<'a, M, B, T, S>
+where
+    M: ScoreModel,
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    type Item = (f32, S);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, score, item) = self.next_item()?;
+        Some((score.unwrap_or_default(), item))
+    }
+}
@@ -0,0 +1,81 @@
+use std::ops::Range;
+
+/// Decides which segments of a `SegmentedIndex` get merged
+pub trait MergePolicy: Send + Sync {
+    /// Returns the range of segments to merge next, given the amount of items in each segment in
+    /// the order of the segments. Returns `None` if no merge is required
+    fn find_merge(&self, sizes: &[usize]) -> Option<Range<usize>>;
+}
+
+/// Groups segments into tiers of exponentially growing sizes and merges `merge_factor`
+/// neighbouring segments of the same tier. Segments smaller than `min_size` are all in the
+/// lowest tier
+pub struct TieredMergePolicy {
+    pub merge_factor: usize,
+    pub min_size: usize,
+}
+
+impl TieredMergePolicy {
+    #[inline]
+    pub fn new(merge_factor: usize, min_size: usize) -> Self {
+        Self {
+            merge_factor,
+            min_size,
+        }
+    }
+
+    /// Returns the amount of segments merged at once
+    #[inline]
+    fn factor(&self) -> usize {
+        self.merge_factor.max(2)
+    }
+
+    /// Returns the tier of a segment with `size` items
+    fn tier(&self, size: usize) -> u32 {
+        let mut bound = self.min_size.max(1);
+        let mut tier = 0;
+        while size >= bound && bound < usize::MAX {
+            bound = bound.saturating_mul(self.factor());
+            tier += 1;
+        }
+        tier
+    }
+}
+
+impl Default for TieredMergePolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::new(10, 1000)
+    }
+}
+
+impl MergePolicy for TieredMergePolicy {
+    fn find_merge(&self, sizes: &[usize]) -> Option<Range<usize>> {
+        let factor = self.factor();
+
+        let mut start = 0;
+        for pos in 1..=sizes.len() {
+            let same_tier = pos < sizes.len() && self.tier(sizes[pos]) == self.tier(sizes[start]);
+            if same_tier {
+                continue;
+            }
+
+            if pos - start >= factor {
+                return Some(start..start + factor);
+            }
+            start = pos;
+        }
+
+        None
+    }
+}
+
+/// Never merges any segments
+pub struct NoMergePolicy;
+
+impl MergePolicy for NoMergePolicy {
+    #[inline]
+    fn find_merge(&self, _sizes: &[usize]) -> Option<Range<usize>> {
+        None
+    }
+}
@@ -0,0 +1,570 @@
+pub mod merge;
+
+use crate::{
+    retrieve::{retriever::Retriever, Retrieve},
+    traits::{
+        backend::Backend, build::IndexBuilder, deser::DeSer, dict_item::DictItem,
+        dictionary::IndexDictionary, postings::IndexPostings, storage::IndexStorage,
+    },
+    Index,
+};
+use merge::{MergePolicy, TieredMergePolicy};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Builds a new segment out of the given segments
+type MergeFn<B, T, S> = dyn Fn(&[Arc<Segment<B, T, S>>]) -> Index<B, T, S> + Send + Sync;
+
+/// Index made of multiple immutable segments. Updates get added as new segments, which are
+/// combined into larger ones by a `MergePolicy`, either on demand or in a background thread.
+/// Queries run on a `Snapshot` and fan out across all segments.
+///
+/// Each term gets a global ID in the order it first appeared in a segment. Global IDs stay the
+/// same when segments get merged. Items are kept in the order their segments were added.
+/// Deleted items of a segment are dropped once it gets merged
+pub struct SegmentedIndex<B, T, S> {
+    inner: Arc<Inner<B, T, S>>,
+}
+
+struct Inner<B, T, S> {
+    state: RwLock<State<B, T, S>>,
+    policy: Box<dyn MergePolicy>,
+    merge_fn: Box<MergeFn<B, T, S>>,
+    /// Whether new segments trigger a merge in a background thread
+    background: AtomicBool,
+    /// Set while a merge is running. Only one merge can run at a time
+    merging: AtomicBool,
+}
+
+struct State<B, T, S> {
+    segments: Vec<Arc<Segment<B, T, S>>>,
+    terms: Arc<TermIds<T>>,
+}
+
+impl<B, T, S> SegmentedIndex<B, T, S>
+where
+    B: Backend<T, S> + Send + Sync + 'static,
+    T: DictItem + Hash + Clone + Send + Sync + 'static,
+    S: DeSer + Send + Sync + 'static,
+{
+    /// Creates a new segmented index using the default `TieredMergePolicy`. `new_builder`
+    /// creates the builders used to merge segments
+    #[inline]
+    pub fn new<BB, F>(new_builder: F) -> Self
+    where
+        BB: IndexBuilder<T, S, ForBackend = B>,
+        F: Fn() -> BB + Send + Sync + 'static,
+    {
+        Self::with_merge_policy(new_builder, TieredMergePolicy::default())
+    }
+
+    /// Creates a new segmented index with a custom merge policy
+    pub fn with_merge_policy<BB, F, P>(new_builder: F, policy: P) -> Self
+    where
+        BB: IndexBuilder<T, S, ForBackend = B>,
+        F: Fn() -> BB + Send + Sync + 'static,
+        P: MergePolicy + 'static,
+    {
+        let merge_fn =
+            move |segments: &[Arc<Segment<B, T, S>>]| merge_segments(segments, new_builder());
+
+        let state = State {
+            segments: vec![],
+            terms: Arc::new(TermIds::default()),
+        };
+
+        let inner = Inner {
+            state: RwLock::new(state),
+            policy: Box::new(policy),
+            merge_fn: Box::new(merge_fn),
+            background: AtomicBool::new(false),
+            merging: AtomicBool::new(false),
+        };
+
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Enables or disables merging in a background thread whenever a segment gets added
+    #[inline]
+    pub fn set_background_merge(&self, enabled: bool) {
+        self.inner.background.store(enabled, Ordering::Release);
+    }
+
+    /// Adds a new segment after all existing ones
+    pub fn add_segment(&self, index: Index<B, T, S>) {
+        {
+            let mut state = self.inner.state.write().unwrap();
+            let segment = Segment::new(index, &state.terms);
+            state.segments.push(Arc::new(segment));
+        }
+
+        if self.inner.background.load(Ordering::Acquire) {
+            self.spawn_merge();
+        }
+    }
+
+    /// Returns a consistent view of the current segments to run queries on
+    #[inline]
+    pub fn snapshot(&self) -> Snapshot<B, T, S> {
+        let state = self.inner.state.read().unwrap();
+        Snapshot {
+            segments: state.segments.clone(),
+            terms: state.terms.clone(),
+            term_count: state.terms.len(),
+        }
+    }
+
+    /// Returns the amount of segments
+    #[inline]
+    pub fn segment_count(&self) -> usize {
+        self.inner.state.read().unwrap().segments.len()
+    }
+
+    /// Runs a single merge chosen by the merge policy. Returns `false` if there was nothing to
+    /// merge or another merge is running
+    pub fn merge(&self) -> bool {
+        match MergeGuard::acquire(&self.inner.merging) {
+            Some(_guard) => self.merge_step(),
+            None => false,
+        }
+    }
+
+    /// Merges segments until the merge policy finds nothing to merge. Returns the amount of
+    /// merges done
+    pub fn merge_all(&self) -> usize {
+        let _guard = match MergeGuard::acquire(&self.inner.merging) {
+            Some(guard) => guard,
+            None => return 0,
+        };
+
+        let mut count = 0;
+        while self.merge_step() {
+            count += 1;
+        }
+        count
+    }
+
+    /// Runs `merge_all` in a background thread. Returns `None` if another merge is running
+    pub fn spawn_merge(&self) -> Option<JoinHandle<usize>> {
+        if self.inner.merging.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let this = self.clone();
+        Some(thread::spawn(move || this.merge_all()))
+    }
+
+    /// Merges the next segments chosen by the policy. The merge flag has to be held
+    fn merge_step(&self) -> bool {
+        let (range, segments) = {
+            let state = self.inner.state.read().unwrap();
+            let sizes: Vec<_> = state.segments.iter().map(|i| i.len()).collect();
+            let range = match self.inner.policy.find_merge(&sizes) {
+                Some(r) if r.len() > 1 && r.end <= sizes.len() => r,
+                _ => return false,
+            };
+            (range.clone(), state.segments[range].to_vec())
+        };
+
+        // Building the new segment is the expensive part and doesn't block queries or new
+        // segments
+        let merged = (self.inner.merge_fn)(&segments);
+
+        let mut state = self.inner.state.write().unwrap();
+        // Segments only get removed by merges, so the merged ones are still at the same place
+        debug_assert!(state.segments[range.clone()]
+            .iter()
+            .zip(&segments)
+            .all(|(a, b)| Arc::ptr_eq(a, b)));
+
+        let segment = Segment::new(merged, &state.terms);
+        state.segments.splice(range, [Arc::new(segment)]);
+        true
+    }
+}
+
+impl<B, T, S> Clone for SegmentedIndex<B, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// A single segment of a `SegmentedIndex`
+pub struct Segment<B, T, S> {
+    index: Index<B, T, S>,
+    /// Global term IDs by the segments term IDs
+    global_ids: Vec<u32>,
+    /// Segment term IDs by global term IDs
+    local_ids: HashMap<u32, u32>,
+}
+
+impl<B, T, S> Segment<B, T, S>
+where
+    B: Backend<T, S>,
+    T: DictItem + Hash + Clone,
+    S: DeSer,
+{
+    /// Creates a new segment and assigns global IDs to all of its new terms
+    fn new(index: Index<B, T, S>, terms: &TermIds<T>) -> Self {
+        let dict = index.dict();
+        let global_ids =
+            terms.get_or_insert((0..dict.len() as u32).map(|id| dict.get_term(id).unwrap()));
+
+        let local_ids = global_ids
+            .iter()
+            .enumerate()
+            .map(|(local, global)| (*global, local as u32))
+            .collect();
+
+        Self {
+            index,
+            global_ids,
+            local_ids,
+        }
+    }
+}
+
+impl<B, T, S> Segment<B, T, S>
+where
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    /// Returns the index of the segment
+    #[inline]
+    pub fn index(&self) -> &Index<B, T, S> {
+        &self.index
+    }
+
+    /// Returns the global ID of a term ID of this segment
+    #[inline]
+    pub fn global_term_id(&self, id: u32) -> Option<u32> {
+        self.global_ids.get(id as usize).copied()
+    }
+
+    /// Returns the term ID of this segment for a global term ID
+    #[inline]
+    pub fn local_term_id(&self, id: u32) -> Option<u32> {
+        self.local_ids.get(&id).copied()
+    }
+
+    /// Returns the amount of items in the segment
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.index.storage().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Global term IDs in the order the terms were added. Terms only ever get appended, so all
+/// snapshots share one table and ignore the terms added after they were taken
+struct TermIds<T> {
+    table: RwLock<TermTable<T>>,
+}
+
+struct TermTable<T> {
+    ids: HashMap<T, u32>,
+    terms: Vec<T>,
+}
+
+impl<T: Hash + Eq + Clone> TermIds<T> {
+    /// Returns the global IDs of all given terms. New terms get appended
+    fn get_or_insert<I>(&self, terms: I) -> Vec<u32>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut table = self.table.write().unwrap();
+        let table = &mut *table;
+        terms
+            .into_iter()
+            .map(|term| {
+                if let Some(id) = table.ids.get(&term) {
+                    return *id;
+                }
+                let id = table.terms.len() as u32;
+                table.terms.push(term.clone());
+                table.ids.insert(term, id);
+                id
+            })
+            .collect()
+    }
+}
+
+impl<T> TermIds<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.table.read().unwrap().terms.len()
+    }
+}
+
+impl<T> Default for TermIds<T> {
+    #[inline]
+    fn default() -> Self {
+        let table = TermTable {
+            ids: HashMap::new(),
+            terms: vec![],
+        };
+        Self {
+            table: RwLock::new(table),
+        }
+    }
+}
+
+/// View of the segments of a `SegmentedIndex` at some point in time. Not affected by later
+/// added or merged segments
+pub struct Snapshot<B, T, S> {
+    segments: Vec<Arc<Segment<B, T, S>>>,
+    terms: Arc<TermIds<T>>,
+    /// Amount of terms when the snapshot was taken
+    term_count: usize,
+}
+
+impl<B, T, S> Snapshot<B, T, S>
+where
+    B: Backend<T, S>,
+    T: DictItem + Hash + Clone,
+    S: DeSer,
+{
+    /// Returns all segments in their order
+    #[inline]
+    pub fn segments(&self) -> &[Arc<Segment<B, T, S>>] {
+        &self.segments
+    }
+
+    /// Returns the amount of items in all segments
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|i| i.len()).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the global ID of a term
+    #[inline]
+    pub fn term_id(&self, term: &T) -> Option<u32> {
+        let table = self.terms.table.read().unwrap();
+        let id = *table.ids.get(term)?;
+        ((id as usize) < self.term_count).then_some(id)
+    }
+
+    /// Returns the term with the given global ID
+    #[inline]
+    pub fn term(&self, id: u32) -> Option<T> {
+        if id as usize >= self.term_count {
+            return None;
+        }
+        self.terms
+            .table
+            .read()
+            .unwrap()
+            .terms
+            .get(id as usize)
+            .cloned()
+    }
+
+    /// Returns the amount of terms in all segments
+    #[inline]
+    pub fn term_count(&self) -> usize {
+        self.term_count
+    }
+
+    /// Runs the retrieve built by `f` on each segment and returns the results segment by
+    /// segment. The offset and limit of the retrieve apply to all results
+    #[inline]
+    pub fn search<'a, R, F>(&'a self, f: F) -> Vec<R::Output>
+    where
+        R: Retriever<'a, B, T, S>,
+        F: Fn(Retrieve<'a, B, T, S>) -> Retrieve<'a, B, T, S>,
+    {
+        self.fan_out::<R, _>(|_, retrieve| f(retrieve), true)
+    }
+
+    /// Same as `search` but queries the given global term IDs
+    #[inline]
+    pub fn search_term_ids<'a, R, F>(&'a self, ids: &[u32], f: F) -> Vec<R::Output>
+    where
+        R: Retriever<'a, B, T, S>,
+        F: Fn(Retrieve<'a, B, T, S>) -> Retrieve<'a, B, T, S>,
+    {
+        self.fan_out::<R, _>(
+            |segment, retrieve| {
+                let local = ids.iter().filter_map(|i| segment.local_term_id(*i));
+                f(retrieve.by_term_ids(local))
+            },
+            true,
+        )
+    }
+
+    /// Runs a scoring retriever on each segment and returns all results ordered by descending
+    /// score. Scores are calculated with the statistics of each segment
+    pub fn search_scored<'a, R, F>(&'a self, f: F) -> Vec<(f32, S)>
+    where
+        R: Retriever<'a, B, T, S, Output = (f32, S)>,
+        F: Fn(Retrieve<'a, B, T, S>) -> Retrieve<'a, B, T, S>,
+    {
+        let (mut offset, mut limit) = (0, 0);
+        let mut out = self.fan_out::<R, _>(
+            |_, retrieve| {
+                let retrieve = f(retrieve);
+                (offset, limit) = (retrieve.offset(), retrieve.limit());
+                retrieve
+            },
+            false,
+        );
+
+        // Stable, so equal scores keep the segment order
+        out.sort_by(|a, b| b.0.total_cmp(&a.0));
+        out.drain(..offset.min(out.len()));
+        if limit > 0 {
+            out.truncate(limit);
+        }
+        out
+    }
+
+    /// Runs the retrieve built by `f` on each segment. Each segment returns all results up to the
+    /// limit of the retrieve plus its offset. If `stop_at_limit` is set, the offset and limit get
+    /// applied to the results of all segments and no more segments are queried once the limit is
+    /// reached
+    fn fan_out<'a, R, F>(&'a self, mut f: F, stop_at_limit: bool) -> Vec<R::Output>
+    where
+        R: Retriever<'a, B, T, S>,
+        F: FnMut(&'a Segment<B, T, S>, Retrieve<'a, B, T, S>) -> Retrieve<'a, B, T, S>,
+    {
+        let (mut offset, mut limit) = (0, 0);
+        let mut out = vec![];
+        for segment in &self.segments {
+            let retrieve = f(segment, segment.index.retrieve());
+            (offset, limit) = (retrieve.offset(), retrieve.limit());
+            let window = retrieve.window_len();
+            out.extend(retrieve.with_offset(0).with_limit(window).get::<R>());
+
+            if stop_at_limit && window > 0 && out.len() >= window {
+                break;
+            }
+        }
+
+        if stop_at_limit {
+            out.drain(..offset.min(out.len()));
+            if limit > 0 {
+                out.truncate(limit);
+            }
+        }
+        out
+    }
+}
+
+/// `(term, position)` pairs of an item by postings
+type ItemTerms = Vec<Vec<(u32, Option<u32>)>>;
+
+/// Rebuilds all items of the given segments in their order with `builder`. Deleted items get
+/// dropped. Term positions are carried over if the postings store them
+fn merge_segments<BB, B, T, S>(
+    segments: &[Arc<Segment<B, T, S>>],
+    mut builder: BB,
+) -> Index<B, T, S>
+where
+    BB: IndexBuilder<T, S, ForBackend = B>,
+    B: Backend<T, S>,
+    T: DictItem,
+    S: DeSer,
+{
+    for segment in segments {
+        let backend: &B = &segment.index;
+        let posting_count = backend.posting_count();
+
+        let mut items: Vec<ItemTerms> = vec![vec![vec![]; posting_count]; backend.storage().len()];
+
+        for t_id in 0..backend.dict().len() as u32 {
+            let mut new_id = None;
+
+            let postings_list =
+                (0..posting_count).filter_map(|p_id| Some((p_id, backend.postings(p_id as u32)?)));
+            for (p_id, postings) in postings_list {
+                let list = postings
+                    .term_positions(t_id)
+                    .unwrap_or_else(|| postings.cursor(t_id).map(|i| (i, vec![])).collect());
+
+                for (item, positions) in list {
+                    if backend.is_deleted(item) {
+                        continue;
+                    }
+                    let id = *new_id.get_or_insert_with(|| {
+                        let term = backend.dict().get_term(t_id).unwrap();
+                        builder.insert_term(term).unwrap_or_else(|v| v)
+                    });
+                    let terms = &mut items[item as usize][p_id];
+                    if positions.is_empty() {
+                        terms.push((id, None));
+                    }
+                    terms.extend(positions.into_iter().map(|pos| (id, Some(pos))));
+                }
+            }
+        }
+
+        for (item, postings) in items.into_iter().enumerate() {
+            if backend.is_deleted(item as u32) {
+                continue;
+            }
+
+            let new_item = builder.insert_item(backend.storage().get_item(item as u32).unwrap());
+            for (p_id, terms) in postings.into_iter().enumerate() {
+                if terms.is_empty() {
+                    continue;
+                }
+                let (positioned, plain): (Vec<_>, Vec<_>) =
+                    terms.into_iter().partition(|i| i.1.is_some());
+                if !plain.is_empty() {
+                    let term_ids: Vec<_> = plain.into_iter().map(|i| i.0).collect();
+                    builder.map(p_id as u32, new_item, &term_ids);
+                }
+                if !positioned.is_empty() {
+                    let terms: Vec<_> = positioned
+                        .into_iter()
+                        .map(|i| (i.0, i.1.unwrap()))
+                        .collect();
+                    builder.map_positioned(p_id as u32, new_item, &terms);
+                }
+            }
+        }
+    }
+
+    builder.build()
+}
+
+/// Holds the merge flag of a segmented index
+struct MergeGuard<'a>(&'a AtomicBool);
+
+impl<'a> MergeGuard<'a> {
+    #[inline]
+    fn acquire(flag: &'a AtomicBool) -> Option<Self> {
+        flag.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+        Some(Self(flag))
+    }
+}
+
+impl<'a> Drop for MergeGuard<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,133 @@
+use compressed_vec::CVec;
+use serde::{Deserialize, Serialize};
+
+/// Statistics about the indexed items, collected while building an index.
+/// Required by retrievers that score their results
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct IndexStats {
+    postings: Vec<PostingStats>,
+}
+
+/// Statistics of all items indexed in a single postings list
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct PostingStats {
+    /// Amount of terms each item was indexed with
+    item_lens: CVec,
+    /// Sum of all item lengths
+    total_len: u64,
+    /// Amount of items indexed in the postings
+    item_count: u32,
+}
+
+impl IndexStats {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new empty statistics for `postings_len` postings
+    #[inline]
+    pub fn with_postings_len(postings_len: usize) -> Self {
+        let postings = (0..postings_len).map(|_| PostingStats::default()).collect();
+        Self { postings }
+    }
+
+    /// Returns the stats of the postings with the given ID
+    #[inline]
+    pub fn posting(&self, id: u32) -> Option<&PostingStats> {
+        self.postings.get(id as usize)
+    }
+
+    /// Returns the mutable stats of the postings with the given ID
+    #[inline]
+    pub fn posting_mut(&mut self, id: u32) -> Option<&mut PostingStats> {
+        self.postings.get_mut(id as usize)
+    }
+
+    /// Returns the amount of postings with statistics
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.postings.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.postings.is_empty()
+    }
+
+    /// Removes the given item from the statistics of all postings
+    #[inline]
+    pub fn remove(&mut self, item: u32) {
+        for posting in &mut self.postings {
+            posting.remove(item);
+        }
+    }
+
+    /// Drops the entries of all items for which `f` returns `false`. Following items move up
+    pub(crate) fn retain_items<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> bool,
+    {
+        for posting in &mut self.postings {
+            let lens = posting.item_lens.as_vec().into_iter().enumerate();
+            let lens: Vec<_> = lens.filter(|(id, _)| f(*id as u32)).map(|i| i.1).collect();
+            posting.item_lens = CVec::from(lens);
+        }
+    }
+}
+
+impl PostingStats {
+    /// Adds `len` to the length of an item
+    pub fn add(&mut self, item: u32, len: u32) {
+        while self.item_lens.len() <= item as usize {
+            self.item_lens.push(0);
+        }
+
+        let old = self.item_lens.get(item as usize).unwrap();
+        if old == 0 && len > 0 {
+            self.item_count += 1;
+        }
+
+        self.item_lens.set(item as usize, old + len);
+        self.total_len += len as u64;
+    }
+
+    /// Removes an item from the statistics
+    pub fn remove(&mut self, item: u32) {
+        let len = self.item_len(item);
+        if len == 0 {
+            return;
+        }
+
+        self.item_lens.set(item as usize, 0);
+        self.total_len -= len as u64;
+        self.item_count -= 1;
+    }
+
+    /// Returns the amount of terms the given item is indexed with
+    #[inline]
+    pub fn item_len(&self, item: u32) -> u32 {
+        self.item_lens.get(item as usize).unwrap_or(0)
+    }
+
+    /// Returns the amount of items indexed in the postings
+    #[inline]
+    pub fn item_count(&self) -> u32 {
+        self.item_count
+    }
+
+    /// Returns the sum of the lengths of all items
+    #[inline]
+    pub fn total_len(&self) -> u64 {
+        self.total_len
+    }
+
+    /// Returns the average item length
+    #[inline]
+    pub fn avg_len(&self) -> f32 {
+        if self.item_count == 0 {
+            return 0.0;
+        }
+        self.total_len as f32 / self.item_count as f32
+    }
+}
@@ -1,10 +1,15 @@
 use super::{
-    deser::DeSer, dict_item::DictItem, dictionary::IndexDictionary, postings::IndexPostings,
-    storage::IndexStorage,
+    deser::DeSer, dict_item::DictItem, dictionary::IndexDictionary, kind::Kind,
+    postings::IndexPostings, storage::IndexStorage,
+};
+use crate::{
+    error::Error,
+    stats::IndexStats,
+    utils::header::{ChecksumReader, ChecksumWriter, Header},
 };
 use std::{
     fs::File,
-    io::{BufReader, Cursor, Read, Write},
+    io::{self, BufReader, BufWriter, Cursor, Read, Write},
     path::Path,
 };
 
@@ -31,26 +36,64 @@ where
     /// Returns the amount of posting maps
     fn posting_count(&self) -> usize;
 
+    /// Returns statistics about the indexed items if the backend has some
+    #[inline]
+    fn stats(&self) -> Option<&IndexStats> {
+        None
+    }
+
+    /// Returns `true` if the item with the given ID was deleted. Deleted items are skipped by
+    /// all retrievers
+    #[inline]
+    fn is_deleted(&self, _id: u32) -> bool {
+        false
+    }
+
     /// Returns `true` if the index doesn't contain index data
     #[inline]
     fn is_empty(&self) -> bool {
         self.dict().is_empty() || self.posting_count() == 0 || self.storage().is_empty()
     }
 
-    fn encode(&self) -> Vec<u8>;
+    /// Encodes the raw index data without a header
+    fn encode(&self) -> Result<Vec<u8>, Error>;
 
-    /// Encodes the index into a writer
-    fn encode_to<W: Write>(&self, mut out: W) -> Result<(), std::io::Error> {
-        let encoded = self.encode();
-        out.write_all(&encoded)
+    /// Encodes the raw index data without a header into a writer
+    #[inline]
+    fn encode_into<W: Write>(&self, mut out: W) -> Result<(), Error> {
+        out.write_all(&self.encode()?)?;
+        Ok(())
     }
 
-    fn decode_from<R: Read>(reader: R) -> Option<Self>
+    /// Encodes the index into a writer. The data is prefixed with a header describing the
+    /// format version, the component kinds and a checksum of the data. The index gets encoded
+    /// twice, first to calculate the checksum and then into `out`, so it never has to be held
+    /// in memory as a whole
+    fn encode_to<W: Write>(&self, mut out: W) -> Result<(), Error>
+    where
+        Self: Sized,
+        Self::Dict: Kind,
+        Self::Postings: Kind,
+        Self::Storage: Kind,
+    {
+        let mut checksum = ChecksumWriter::new(io::sink());
+        self.encode_into(&mut checksum)?;
+        let (len, checksum) = checksum.finish();
+        Header::with_checksum::<Self, T, S>(len, checksum).write(&mut out)?;
+
+        let mut out = BufWriter::new(out);
+        self.encode_into(&mut out)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Decodes the raw index data without a header
+    fn decode_from<R: Read>(reader: R) -> Result<Self, Error>
     where
         Self: Sized;
 
     /// Decodes an idnex from raw bytes
-    fn decode(data: &[u8]) -> Option<Self>
+    fn decode(data: &[u8]) -> Result<Self, Error>
     where
         Self: Sized,
     {
@@ -58,13 +101,37 @@ where
         Self::decode_from(r)
     }
 
-    /// Opens an index backend
-    fn open<P: AsRef<Path>>(file: P) -> Option<Self>
+    /// Reads an index written by `encode_to` and validates its header. The data is decoded
+    /// while reading it
+    fn read_from<R: Read>(mut reader: R) -> Result<Self, Error>
     where
         Self: Sized,
+        Self::Dict: Kind,
+        Self::Postings: Kind,
+        Self::Storage: Kind,
     {
-        let r = BufReader::new(File::open(file).ok()?);
-        Self::decode_from(r)
+        let header = Header::read(&mut reader)?;
+        header.check_types::<Self, T, S>()?;
+
+        let mut payload = ChecksumReader::new(reader.take(header.payload_len));
+        let decoded = Self::decode_from(&mut payload);
+        let (len, checksum) = payload.finish()?;
+        // A damaged payload is reported as such even if decoding failed
+        header.check_checksum(len, checksum)?;
+
+        decoded
+    }
+
+    /// Opens an index backend from a file written by `encode_to`
+    fn open<P: AsRef<Path>>(file: P) -> Result<Self, Error>
+    where
+        Self: Sized,
+        Self::Dict: Kind,
+        Self::Postings: Kind,
+        Self::Storage: Kind,
+    {
+        let r = BufReader::new(File::open(file)?);
+        Self::read_from(r)
     }
 }
 
@@ -76,10 +143,39 @@ where
     fn storage_mut(&mut self) -> &mut Self::Storage;
 }
 
+/// Backends that allow adding and deleting items after the index was built
+pub trait BackendMut<T, S>: Backend<T, S>
+where
+    T: DictItem,
+    S: DeSer,
+{
+    /// Adds a new item and maps it to `terms` in the postings with the given ID. Terms that are
+    /// not in the dictionary yet get added. Each postings-list contains the new item once, even
+    /// if a term is passed multiple times. Returns the ID of the new item or an error if the item
+    /// or a new term couldn't be encoded
+    fn add_item(&mut self, postings_id: u32, item: S, terms: Vec<T>) -> Result<u32, Error>;
+
+    /// Marks the item with the given ID as deleted. Returns `false` if there is no such item or
+    /// it was deleted already
+    fn delete_item(&mut self, id: u32) -> bool;
+
+    /// Returns the amount of deleted items that weren't removed by `compact` yet
+    fn deleted_count(&self) -> usize;
+
+    /// Physically removes all deleted items from the storage and postings. The IDs of the
+    /// remaining items move up to fill the gaps, keeping their order. Also reclaims space left
+    /// behind by updated postings lists. Returns the amount of removed items
+    fn compact(&mut self) -> usize;
+}
+
 pub trait NewBackend<T, S>: Backend<T, S>
 where
     T: DictItem,
     S: DeSer,
 {
     fn new(dict: Self::Dict, postings: Vec<Self::Postings>, storage: Self::Storage) -> Self;
+
+    /// Sets the item statistics. Backends without support for statistics ignore them
+    #[inline]
+    fn set_stats(&mut self, _stats: IndexStats) {}
 }
@@ -1,5 +1,5 @@
 use super::{backend::Backend, deser::DeSer, dict_item::DictItem};
-use crate::Index;
+use crate::{analysis::Analyzer, error::Error, Index};
 
 /// "High-level" interface to allow building indexes
 pub trait IndexBuilder<T, S>
@@ -19,6 +19,14 @@ where
     // Manually maps an item-id to term-ids in the inverted index
     fn map(&mut self, postings_id: u32, item: u32, terms: &[u32]);
 
+    /// Maps an item to term-ids along with the position of each term within the item.
+    /// `terms` contains `(term_id, position)` pairs. By default the positions get dropped
+    #[inline]
+    fn map_positioned(&mut self, postings_id: u32, item: u32, terms: &[(u32, u32)]) {
+        let term_ids: Vec<_> = terms.iter().map(|i| i.0).collect();
+        self.map(postings_id, item, &term_ids);
+    }
+
     // Inserts an item into the index and directly maps it
     fn index_new(&mut self, postings_id: u32, item: S, terms: &[u32]) -> u32 {
         let item_id = self.insert_item(item);
@@ -38,6 +46,18 @@ where
         0
     }
 
+    /// Inserts an item into the index and maps it to the terms `analyzer` produces for `text`.
+    /// Returns the ID of the new item
+    fn index_text(&mut self, pst_id: u32, item: S, text: &str, analyzer: &Analyzer) -> u32
+    where
+        String: Into<T>,
+    {
+        let item_id = self.insert_item(item);
+        let terms = self.terms_to_ids(analyzer.analyze(text));
+        self.map(pst_id, item_id, &terms);
+        item_id
+    }
+
     /// Generate the index
     fn build(self) -> Index<Self::ForBackend, T, S>;
 
@@ -55,5 +75,5 @@ where
 }
 
 pub trait ItemMod<T> {
-    fn set_item(&mut self, id: u32, new: T);
+    fn set_item(&mut self, id: u32, new: T) -> Result<(), Error>;
 }
@@ -1,23 +1,30 @@
+use crate::error::Error;
 use serde::{de::DeserializeOwned, Serialize};
-use std::io::Read;
+use std::io::{Read, Write};
 
 /// Generalizing & Simplifying serailizing and deserializing
 pub trait DeSer: Serialize + DeserializeOwned {
     #[inline(always)]
-    fn encode_vec(&self) -> Vec<u8> {
+    fn encode_vec(&self) -> Result<Vec<u8>, Error> {
         let mut out = Vec::with_capacity(10);
-        bincode::serialize_into(&mut out, self).expect("Encoding failed");
-        out
+        bincode::serialize_into(&mut out, self)?;
+        Ok(out)
     }
 
+    /// Encodes the value directly into a writer
     #[inline(always)]
-    fn decode_vec(data: &[u8]) -> Option<Self> {
-        bincode::deserialize(data).ok()
+    fn encode_into<W: Write>(&self, out: W) -> Result<(), Error> {
+        Ok(bincode::serialize_into(out, self)?)
     }
 
     #[inline(always)]
-    fn decode<R: Read>(reader: R) -> Option<Self> {
-        bincode::deserialize_from(reader).ok()
+    fn decode_vec(data: &[u8]) -> Result<Self, Error> {
+        Ok(bincode::deserialize(data)?)
+    }
+
+    #[inline(always)]
+    fn decode<R: Read>(reader: R) -> Result<Self, Error> {
+        Ok(bincode::deserialize_from(reader)?)
     }
 }
 
@@ -1,5 +1,9 @@
 use super::dict_item::DictItem;
-use std::marker::PhantomData;
+use crate::{error::Error, utils::levenshtein::LevenshteinAutomaton};
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
 
 /// Dictionary containing all terms of the index
 pub trait IndexDictionary<I: DictItem> {
@@ -17,9 +21,157 @@ pub trait IndexDictionary<I: DictItem> {
 
     fn has_term_id(&self, id: u32) -> bool;
 
+    /// Returns the ID of the term at the given position of the lexicographically sorted terms
+    fn sort_pos(&self, pos: usize) -> Option<u32>;
+
     /// Returs the amount of terms in the dictionary
     fn len(&self) -> usize;
 
+    /// Returns the sort position of the first term for which `pred` returns `false`.
+    /// `pred` has to be `true` for all terms before and `false` for all terms after
+    /// this position. Terms that can't be read are skipped
+    fn partition_point<P>(&self, mut pred: P) -> usize
+    where
+        P: FnMut(&I) -> bool,
+    {
+        let (mut left, mut right) = (0, self.len());
+        while left < right {
+            let mid = left + (right - left) / 2;
+            // Compare with the next readable term instead
+            let next = (mid..right).find_map(|pos| {
+                let term = self.sort_pos(pos).and_then(|id| self.get_term(id))?;
+                Some((pos, term))
+            });
+            match next {
+                Some((pos, term)) if pred(&term) => left = pos + 1,
+                _ => right = mid,
+            }
+        }
+        left
+    }
+
+    /// Returns the sort position of the first term that is not smaller than `term`
+    #[inline]
+    fn lower_bound<F: Into<I>>(&self, term: F) -> usize {
+        let term = term.into();
+        self.partition_point(|i| *i < term)
+    }
+
+    /// Returns an iterator over all terms within the given range in lexicographic order
+    fn range<R>(&self, range: R) -> SortedIter<Self, I>
+    where
+        Self: Sized,
+        R: RangeBounds<I>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(s) => self.partition_point(|i| i < s),
+            Bound::Excluded(s) => self.partition_point(|i| i <= s),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => self.partition_point(|i| i <= e),
+            Bound::Excluded(e) => self.partition_point(|i| i < e),
+            Bound::Unbounded => self.len(),
+        };
+        SortedIter::new(self, start, end.max(start))
+    }
+
+    /// Returns an iterator over all terms starting with `prefix` in lexicographic order
+    fn terms_with_prefix(&self, prefix: &str) -> SortedIter<Self, I>
+    where
+        Self: Sized,
+        I: AsRef<str>,
+    {
+        // All terms with the prefix are placed right after the terms smaller than the prefix
+        let start = self.partition_point(|i| i.as_ref() < prefix);
+        let end = self.partition_point(|i| i.as_ref() < prefix || i.as_ref().starts_with(prefix));
+        SortedIter::new(self, start, end)
+    }
+
+    /// Returns the IDs of all terms within the edit distance `max_dist` of `term` along with their
+    /// distance, in lexicographic order. Subtrees of the sorted terms which can't match anymore
+    /// are skipped, as well as terms that can't be read
+    fn fuzzy_terms(&self, term: &str, max_dist: u32) -> Vec<(u32, u32)>
+    where
+        I: AsRef<str>,
+    {
+        let automaton = LevenshteinAutomaton::new(term, max_dist);
+        let mut out = vec![];
+
+        // Automaton states after each char of `prefix`
+        let mut states = vec![automaton.start()];
+        let mut prefix: Vec<char> = vec![];
+
+        let mut pos = 0;
+        while pos < self.len() {
+            let (id, term) = match self
+                .sort_pos(pos)
+                .and_then(|id| Some((id, self.get_term(id)?)))
+            {
+                Some(term) => term,
+                None => {
+                    pos += 1;
+                    continue;
+                }
+            };
+            let chars: Vec<char> = term.as_ref().chars().collect();
+
+            // Reuse the states of the prefix shared with the previous term
+            let common = prefix
+                .iter()
+                .zip(&chars)
+                .take_while(|(a, b)| a == b)
+                .count();
+            states.truncate(common + 1);
+            prefix.truncate(common);
+
+            let mut dead = false;
+            for c in &chars[common..] {
+                let state = automaton.step(states.last().unwrap(), *c);
+                prefix.push(*c);
+                if !automaton.can_match(&state) {
+                    dead = true;
+                    break;
+                }
+                states.push(state);
+            }
+
+            if !dead {
+                if let Some(dist) = automaton.distance(states.last().unwrap()) {
+                    out.push((id, dist));
+                }
+                pos += 1;
+                continue;
+            }
+
+            // Skip all terms starting with the prefix that can't match anymore
+            let dead_prefix: String = prefix.iter().collect();
+            pos = self.partition_point(|i| {
+                let i = i.as_ref();
+                i < dead_prefix.as_str() || i.starts_with(dead_prefix.as_str())
+            });
+            prefix.pop();
+        }
+
+        out
+    }
+
+    /// Returns the ID and value of the largest term smaller than `term`
+    #[inline]
+    fn predecessor<F: Into<I>>(&self, term: F) -> Option<(u32, I)> {
+        let pos = self.lower_bound(term).checked_sub(1)?;
+        let id = self.sort_pos(pos)?;
+        Some((id, self.get_term(id)?))
+    }
+
+    /// Returns the ID and value of the smallest term greater than `term`
+    #[inline]
+    fn successor<F: Into<I>>(&self, term: F) -> Option<(u32, I)> {
+        let term = term.into();
+        let id = self.sort_pos(self.partition_point(|i| *i <= term))?;
+        Some((id, self.get_term(id)?))
+    }
+
     /// Returns an iterator over all terms in the dictionary
     #[inline]
     fn iter(&self) -> DictIter<Self, I>
@@ -36,14 +188,38 @@ pub trait IndexDictionary<I: DictItem> {
     }
 }
 
+/// Dictionaries of n-grams. Query texts have to be split into n-grams the same way the indexed
+/// texts were
+pub trait NGramDictionary: IndexDictionary<String> {
+    /// Returns the n-grams of `text` the way they are stored in the dictionary
+    fn ngrams(&self, text: &str) -> Vec<String>;
+}
+
+/// Dictionaries that allow adding terms after building
+pub trait IndexDictionaryMod<I: DictItem>: IndexDictionary<I> {
+    /// Adds all terms that aren't in the dictionary yet. Returns the IDs of all given terms in
+    /// their order. Nothing gets inserted if one of the new terms can't be encoded
+    fn insert_terms(&mut self, terms: Vec<I>) -> Result<Vec<u32>, Error>;
+}
+
 pub trait BuildIndexDictionary<I: DictItem> {
     type Output;
 
     /// Create a new IndexDictionary
     fn new() -> Self;
 
+    /// Creates a new dictionary for merging `a` and `b`, keeping the build settings they share.
+    /// Returns an error if their settings differ
+    #[inline]
+    fn for_merge(_a: &Self::Output, _b: &Self::Output) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Ok(Self::new())
+    }
+
     /// Inserts a new item into the dict and returns its ID
-    fn insert(&mut self, i: I) -> u32;
+    fn insert(&mut self, i: I) -> Result<u32, Error>;
 
     /// Returns a dict item with the given ID
     fn get(&self, id: u32) -> Option<I>;
@@ -89,3 +265,81 @@ where
         Some(item)
     }
 }
+
+/// Iterator over a range of sort positions of a dictionary, yielding the ID and value of
+/// each term in lexicographic order
+pub struct SortedIter<'a, D: IndexDictionary<I>, I: DictItem> {
+    inner: &'a D,
+    p: PhantomData<I>,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a, D, I> SortedIter<'a, D, I>
+where
+    I: DictItem,
+    D: IndexDictionary<I>,
+{
+    #[inline]
+    pub(crate) fn new(inner: &'a D, pos: usize, end: usize) -> Self {
+        Self {
+            inner,
+            p: PhantomData,
+            pos,
+            end,
+        }
+    }
+
+    #[inline]
+    fn item(&self, pos: usize) -> Option<(u32, I)> {
+        let id = self.inner.sort_pos(pos)?;
+        Some((id, self.inner.get_term(id)?))
+    }
+}
+
+impl<'a, D, I> Iterator for SortedIter<'a, D, I>
+where
+    I: DictItem,
+    D: IndexDictionary<I>,
+{
+    type Item = (u32, I);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let item = self.item(self.pos)?;
+        self.pos += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.pos;
+        (len, Some(len))
+    }
+}
+
+impl<'a, D, I> DoubleEndedIterator for SortedIter<'a, D, I>
+where
+    I: DictItem,
+    D: IndexDictionary<I>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let item = self.item(self.end - 1)?;
+        self.end -= 1;
+        Some(item)
+    }
+}
+
+impl<'a, D, I> ExactSizeIterator for SortedIter<'a, D, I>
+where
+    I: DictItem,
+    D: IndexDictionary<I>,
+{
+}
@@ -0,0 +1,6 @@
+/// Stable descriptor of an index component's encoded format. It gets stored in file headers to
+/// detect files written with other components
+pub trait Kind {
+    /// Unique name of the component. Must stay the same as long as its encoded format does
+    const KIND: &'static str;
+}
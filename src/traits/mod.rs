@@ -3,5 +3,6 @@ pub mod build;
 pub mod deser;
 pub mod dict_item;
 pub mod dictionary;
+pub mod kind;
 pub mod postings;
 pub mod storage;
@@ -3,15 +3,47 @@ use std::collections::HashMap;
 /// Postings are inverted mappings from a term to all items within the storage which
 /// are indexed to this term
 pub trait IndexPostings {
+    /// Cursor over a single postings-list
+    type Cursor<'a>: PostingCursor
+    where
+        Self: 'a;
+
     /// Returns the postings-list with a given ID
     fn get_posting(&self, id: u32) -> Vec<u32>;
 
+    /// Returns a cursor walking over the postings-list with the given ID without copying it
+    fn cursor(&self, id: u32) -> Self::Cursor<'_>;
+
     /// Returns `true` if the posting storage has a
     /// posting list with the given ID
     fn has_id(&self, id: u32) -> bool;
 
     fn posting_size(&self, id: u32) -> usize;
 
+    /// Returns all items of the postings-list with the given ID along with the frequency of the
+    /// term in each item. By default, an item occurring multiple times in a list is counted
+    #[inline]
+    fn get_term_freqs(&self, id: u32) -> Vec<(u32, u32)> {
+        let mut posting = self.get_posting(id);
+        posting.sort_unstable();
+
+        let mut out: Vec<(u32, u32)> = Vec::with_capacity(posting.len());
+        for item in posting {
+            match out.last_mut() {
+                Some(last) if last.0 == item => last.1 += 1,
+                _ => out.push((item, 1)),
+            }
+        }
+        out
+    }
+
+    /// Returns all items of the postings-list with the given ID along with the positions of the
+    /// term within each item. Returns `None` if the postings don't store positions
+    #[inline]
+    fn term_positions(&self, _id: u32) -> Option<Vec<(u32, Vec<u32>)>> {
+        None
+    }
+
     /// Returs the amount of postings
     fn len(&self) -> usize;
 
@@ -31,12 +63,97 @@ pub trait IndexPostings {
     }
 }
 
+/// A cursor over the items of a single postings-list
+pub trait PostingCursor: Iterator<Item = u32> {
+    /// Skips all items smaller than `target` and returns the next item.
+    /// Only works as expected on sorted postings-lists
+    #[inline]
+    fn advance_to(&mut self, target: u32) -> Option<u32> {
+        loop {
+            let item = self.next()?;
+            if item >= target {
+                return Some(item);
+            }
+        }
+    }
+}
+
+/// Cursor over a postings-list stored as slice
+#[derive(Clone)]
+pub struct SliceCursor<'a> {
+    items: &'a [u32],
+}
+
+impl<'a> SliceCursor<'a> {
+    #[inline]
+    pub fn new(items: &'a [u32]) -> Self {
+        Self { items }
+    }
+}
+
+impl<'a> Iterator for SliceCursor<'a> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (first, rest) = self.items.split_first()?;
+        self.items = rest;
+        Some(*first)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.items.len(), Some(self.items.len()))
+    }
+}
+
+impl<'a> PostingCursor for SliceCursor<'a> {
+    #[inline]
+    fn advance_to(&mut self, target: u32) -> Option<u32> {
+        let pos = self.items.partition_point(|i| *i < target);
+        self.items = &self.items[pos..];
+        self.next()
+    }
+}
+
+/// Postings which additionally know the positions of a term within the items
+pub trait IndexPositions: IndexPostings {
+    /// Returns all items of the postings-list with the given ID along with
+    /// the positions of the term within each item
+    fn get_positions(&self, id: u32) -> Vec<(u32, Vec<u32>)>;
+}
+
+/// Postings that can be modified after building
+pub trait PostingsMut: IndexPostings {
+    /// Adds an item to the end of the postings-list with the given ID. The list gets created if
+    /// it doesn't exist yet
+    fn add_item(&mut self, id: u32, item: u32);
+
+    /// Adds an item along with the positions of the term within the item. Postings that don't
+    /// store positions ignore them
+    #[inline]
+    fn add_item_positioned(&mut self, id: u32, item: u32, _positions: &[u32]) {
+        self.add_item(id, item);
+    }
+
+    /// Replaces all items by the result of `f`. Items for which `f` returns `None` get removed,
+    /// lists left empty are dropped
+    fn remap_items<F>(&mut self, f: F)
+    where
+        F: FnMut(u32) -> Option<u32>;
+}
+
 pub trait BuildPostings {
     type Output;
     type PostingList;
 
     fn from_map(map: HashMap<u32, Self::PostingList>) -> Self;
 
+    /// Passes the positions of each term within the items, mapped as term -> item -> positions.
+    /// Postings that don't store positions ignore them
+    #[inline]
+    fn set_positions(&mut self, _positions: HashMap<u32, HashMap<u32, Vec<u32>>>) {}
+
     fn build(self) -> Self::Output;
 }
 
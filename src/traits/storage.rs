@@ -1,3 +1,4 @@
+use crate::error::Error;
 use std::marker::PhantomData;
 
 /// Storage containing all 'result' items in the index
@@ -29,6 +30,14 @@ pub trait IndexStorage<I> {
 
 pub trait IndexStorageMod<I>: IndexStorage<I> {
     fn set_item(&mut self, id: u32, new: I) -> bool;
+
+    /// Appends an item to the storage and returns its ID
+    fn push_item(&mut self, item: I) -> Result<u32, Error>;
+
+    /// Removes all items for which `f` returns `false`. Following items move up to fill the gaps
+    fn retain_items<F>(&mut self, f: F)
+    where
+        F: FnMut(u32) -> bool;
 }
 
 pub trait BuildIndexStorage<T> {
@@ -36,7 +45,7 @@ pub trait BuildIndexStorage<T> {
 
     fn new() -> Self;
 
-    fn insert(&mut self, item: T) -> u32;
+    fn insert(&mut self, item: T) -> Result<u32, Error>;
 
     /// Returns a storage item with the given ID
     fn get(&self, id: u32) -> Option<T>;
@@ -0,0 +1,237 @@
+use crate::{
+    error::Error,
+    traits::{backend::Backend, deser::DeSer, dict_item::DictItem, kind::Kind},
+};
+use bincode::Options;
+use crc32fast::Hasher;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+/// Magic bytes every encoded index file starts with
+pub const MAGIC: [u8; 4] = *b"IDXF";
+
+/// Version of the index file format
+pub const VERSION: u32 = 3;
+
+/// Maximum encoded size of the header descriptor. Protects against huge allocations when reading
+/// damaged files
+const MAX_DESCRIPTOR_LEN: u64 = 4096;
+
+/// Header written in front of an encoded index.
+///
+/// Layout: `[magic: 4 bytes][version: u32][bincode encoded descriptor][payload]`
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Kind of the dictionary
+    pub dict: String,
+    /// Kind of the postings
+    pub postings: String,
+    /// Kind of the storage
+    pub storage: String,
+    /// Length of the payload in bytes
+    pub payload_len: u64,
+    /// CRC32 checksum of the payload
+    pub checksum: u32,
+}
+
+impl Header {
+    /// Creates a new header for the given payload of backend `B`
+    #[inline]
+    pub fn new<B, T, S>(payload: &[u8]) -> Self
+    where
+        B: Backend<T, S>,
+        B::Dict: Kind,
+        B::Postings: Kind,
+        B::Storage: Kind,
+        T: DictItem,
+        S: DeSer,
+    {
+        Self::with_checksum::<B, T, S>(payload.len() as u64, crc32fast::hash(payload))
+    }
+
+    /// Creates a new header for a payload of backend `B` with the given length and checksum
+    pub fn with_checksum<B, T, S>(payload_len: u64, checksum: u32) -> Self
+    where
+        B: Backend<T, S>,
+        B::Dict: Kind,
+        B::Postings: Kind,
+        B::Storage: Kind,
+        T: DictItem,
+        S: DeSer,
+    {
+        Self {
+            dict: B::Dict::KIND.to_string(),
+            postings: B::Postings::KIND.to_string(),
+            storage: B::Storage::KIND.to_string(),
+            payload_len,
+            checksum,
+        }
+    }
+
+    /// Writes the header into `out`
+    pub fn write<W: Write>(&self, mut out: W) -> Result<(), Error> {
+        out.write_all(&MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        bincode::serialize_into(out, self)?;
+        Ok(())
+    }
+
+    /// Reads a header from `reader`. Fails if the magic bytes or the format version don't match
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(Error::Corrupt("not an index file".to_string()));
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != VERSION {
+            return Err(Error::VersionMismatch {
+                expected: VERSION,
+                found: version,
+            });
+        }
+
+        // Same encoding as `bincode::serialize_into` used by `write`
+        let options = bincode::options()
+            .with_fixint_encoding()
+            .with_limit(MAX_DESCRIPTOR_LEN);
+        Ok(options.deserialize_from(reader)?)
+    }
+
+    /// Returns the encoded length of the header
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        MAGIC.len() + 4 + bincode::serialized_size(self).unwrap_or(0) as usize
+    }
+
+    /// Checks whether the header describes the components of backend `B`
+    pub fn check_types<B, T, S>(&self) -> Result<(), Error>
+    where
+        B: Backend<T, S>,
+        B::Dict: Kind,
+        B::Postings: Kind,
+        B::Storage: Kind,
+        T: DictItem,
+        S: DeSer,
+    {
+        let check = |component, expected: &str, found: &str| {
+            if expected == found {
+                return Ok(());
+            }
+            Err(Error::TypeMismatch {
+                component,
+                expected: expected.to_string(),
+                found: found.to_string(),
+            })
+        };
+
+        check("dictionary", B::Dict::KIND, &self.dict)?;
+        check("postings", B::Postings::KIND, &self.postings)?;
+        check("storage", B::Storage::KIND, &self.storage)
+    }
+
+    /// Checks the length and checksum of the payload
+    #[inline]
+    pub fn check_payload(&self, payload: &[u8]) -> Result<(), Error> {
+        self.check_checksum(payload.len() as u64, crc32fast::hash(payload))
+    }
+
+    /// Checks the length of a payload
+    pub fn check_len(&self, payload_len: u64) -> Result<(), Error> {
+        if payload_len != self.payload_len {
+            return Err(Error::Corrupt(format!(
+                "expected {} bytes of payload, found {payload_len}",
+                self.payload_len,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the length and checksum of a payload
+    pub fn check_checksum(&self, payload_len: u64, checksum: u32) -> Result<(), Error> {
+        self.check_len(payload_len)?;
+
+        if checksum != self.checksum {
+            return Err(Error::Corrupt("checksum mismatch".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Writer calculating the length and checksum of all data written through it
+pub struct ChecksumWriter<W> {
+    inner: W,
+    hasher: Hasher,
+    len: u64,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Hasher::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the amount of bytes written and their checksum
+    #[inline]
+    pub fn finish(self) -> (u64, u32) {
+        (self.len, self.hasher.finalize())
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len += n as u64;
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader calculating the length and checksum of all data read through it
+pub struct ChecksumReader<R> {
+    inner: R,
+    hasher: Hasher,
+    len: u64,
+}
+
+impl<R: Read> ChecksumReader<R> {
+    #[inline]
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Hasher::new(),
+            len: 0,
+        }
+    }
+
+    /// Reads the remaining data and returns the amount of bytes read and their checksum
+    #[inline]
+    pub fn finish(mut self) -> io::Result<(u64, u32)> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok((self.len, self.hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len += n as u64;
+        Ok(n)
+    }
+}
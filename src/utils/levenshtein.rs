@@ -0,0 +1,49 @@
+/// Levenshtein automaton accepting all words within a maximum edit distance of a query.
+/// States are rows of the Levenshtein DP-matrix
+pub struct LevenshteinAutomaton {
+    query: Vec<char>,
+    max_dist: u32,
+}
+
+impl LevenshteinAutomaton {
+    #[inline]
+    pub fn new(query: &str, max_dist: u32) -> Self {
+        Self {
+            query: query.chars().collect(),
+            max_dist,
+        }
+    }
+
+    /// Returns the state for the empty word
+    #[inline]
+    pub fn start(&self) -> Vec<u32> {
+        (0..=self.query.len() as u32).collect()
+    }
+
+    /// Returns the state after reading `c` in the given state
+    pub fn step(&self, state: &[u32], c: char) -> Vec<u32> {
+        let mut next = Vec::with_capacity(state.len());
+        next.push(state[0] + 1);
+
+        for (i, qc) in self.query.iter().enumerate() {
+            let cost = (*qc != c) as u32;
+            let dist = (state[i] + cost).min(state[i + 1] + 1).min(next[i] + 1);
+            next.push(dist);
+        }
+
+        next
+    }
+
+    /// Returns the edit distance of the word read so far if it is within the maximum distance
+    #[inline]
+    pub fn distance(&self, state: &[u32]) -> Option<u32> {
+        let dist = *state.last()?;
+        (dist <= self.max_dist).then_some(dist)
+    }
+
+    /// Returns `true` if the word read so far can be extended to a matching word
+    #[inline]
+    pub fn can_match(&self, state: &[u32]) -> bool {
+        state.iter().any(|i| *i <= self.max_dist)
+    }
+}
@@ -1,2 +1,5 @@
 pub mod bin_search;
 pub mod const_arr_deser;
+pub mod header;
+pub mod levenshtein;
+pub mod sorted;
@@ -0,0 +1,47 @@
+use intersect_iter::Intersect;
+use std::cmp::Ordering;
+
+/// Returns all items contained in both sorted lists
+#[inline]
+pub fn intersect(a: &[u32], b: &[u32]) -> Vec<u32> {
+    Intersect::new(a.iter(), b.iter()).copied().collect()
+}
+
+/// Returns all items contained in any of the lists. The lists don't have to be sorted
+pub fn union<I, L>(lists: I) -> Vec<u32>
+where
+    I: IntoIterator<Item = L>,
+    L: AsRef<[u32]>,
+{
+    let mut out = vec![];
+    for list in lists {
+        out.extend_from_slice(list.as_ref());
+    }
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/// Returns all items of the sorted list `a` which are not in the sorted list `b`
+pub fn difference(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut b_iter = b.iter().peekable();
+
+    for i in a {
+        while let Some(j) = b_iter.peek() {
+            match (*j).cmp(i) {
+                Ordering::Less => {
+                    b_iter.next();
+                }
+                Ordering::Equal => break,
+                Ordering::Greater => break,
+            }
+        }
+
+        if b_iter.peek() != Some(&i) {
+            out.push(*i);
+        }
+    }
+
+    out
+}
@@ -0,0 +1,94 @@
+use index_framework::{
+    analysis::{
+        filter::{
+            LengthFilter, LowercaseFilter, NormalizationForm, NormalizeFilter, StopwordFilter,
+        },
+        tokenizer::{NGramTokenizer, Tokenizer, UnicodeWordTokenizer, WhitespaceTokenizer},
+        Analyzer,
+    },
+    backend::memory::presets::{Simple, SimpleBuilder},
+    retrieve::retriever::boolean::BoolRetriever,
+    traits::build::IndexBuilder,
+    Index,
+};
+
+#[test]
+fn test_tokenizers() {
+    let text = "The quick-brown  fox, jumps!";
+    assert_eq!(
+        WhitespaceTokenizer.tokenize(text),
+        vec!["The", "quick-brown", "fox,", "jumps!"]
+    );
+    assert_eq!(
+        UnicodeWordTokenizer.tokenize(text),
+        vec!["The", "quick", "brown", "fox", "jumps"]
+    );
+    assert_eq!(
+        UnicodeWordTokenizer.tokenize("日本語 can't"),
+        vec!["日", "本", "語", "can't"]
+    );
+
+    assert_eq!(
+        NGramTokenizer::new(2).tokenize("日本語"),
+        vec!["日本", "本語"]
+    );
+    assert_eq!(NGramTokenizer::new(3).tokenize("ab"), vec!["ab"]);
+    assert!(NGramTokenizer::new(3).tokenize("").is_empty());
+}
+
+#[test]
+fn test_analyzer() {
+    let analyzer = Analyzer::new(WhitespaceTokenizer)
+        .with_filter(NormalizeFilter::new(NormalizationForm::Nfkc))
+        .with_filter(LowercaseFilter)
+        .with_filter(StopwordFilter::new(["the", "a"]))
+        .with_filter(LengthFilter::new(2, 5));
+
+    assert_eq!(
+        analyzer.analyze("The ＦＯＸ ate a Pineapple x Ｔea"),
+        vec!["fox", "ate", "tea"]
+    );
+    assert!(analyzer.analyze("").is_empty());
+
+    // Filters run in order: the stopword is only matched after lowercasing
+    let analyzer = Analyzer::new(WhitespaceTokenizer)
+        .with_filter(StopwordFilter::new(["the"]))
+        .with_filter(LowercaseFilter);
+    assert_eq!(analyzer.analyze("The the"), vec!["the"]);
+
+    let composed = "e\u{301}";
+    let nfc = NormalizeFilter::new(NormalizationForm::Nfc);
+    let analyzer = Analyzer::new(WhitespaceTokenizer).with_filter(nfc);
+    assert_eq!(analyzer.analyze(composed), vec!["\u{e9}"]);
+}
+
+#[test]
+fn test_index_text() {
+    let docs = [
+        "The quick brown fox.",
+        "A lazy, lazy dog!",
+        "Quick thinking: brown dogs",
+    ];
+
+    let analyzer = Analyzer::default().with_filter(StopwordFilter::new(["the", "a"]));
+
+    let mut builder = SimpleBuilder::<String, u32>::new();
+    for (pos, doc) in docs.iter().enumerate() {
+        let id = builder.index_text(0, pos as u32, doc, &analyzer);
+        assert_eq!(id, pos as u32);
+    }
+    let index: Index<Simple<String, u32>, String, u32> = builder.build();
+
+    let find = |query: &str| {
+        index
+            .retrieve()
+            .by_text(query, &analyzer)
+            .get_all::<BoolRetriever<_, _, _>>()
+    };
+
+    assert_eq!(find("QUICK"), vec![0, 2]);
+    assert_eq!(find("Brown, quick?"), vec![0, 2]);
+    assert_eq!(find("the dog"), vec![1]);
+    assert_eq!(find("dogs"), vec![2]);
+    assert!(find("the").is_empty());
+}
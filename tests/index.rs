@@ -1,22 +1,39 @@
 use index_framework::{
     backend::memory::{
-        build::MemIndexBuilder,
-        dict::default::Dictionary,
-        postings::{compressed, default},
+        build::{
+            external::ExternalIndexBuilder, options::BuildOption, parallel::ParallelIndexBuilder,
+            MemIndexBuilder,
+        },
+        dict::{
+            default::Dictionary,
+            front_coded::{FrontCodedDict, FrontCodedDictBuilder},
+            fst::{FstDict, FstDictBuilder},
+        },
+        postings::{bitmap, compressed, default, tf},
         storage::default::Storage,
         MemBackend,
     },
-    retrieve::retriever::default::DefaultRetrieve,
+    error::Error,
+    retrieve::{
+        query::BoolQuery,
+        retriever::{
+            boolean::{BitmapBoolRetriever, BoolRetriever},
+            default::DefaultRetrieve,
+            scored::{Bm25Retriever, TfIdfRetriever},
+        },
+    },
     traits::{
-        backend::Backend,
+        backend::{Backend, BackendMut},
         build::IndexBuilder,
         dictionary::{BuildIndexDictionary, IndexDictionary},
         postings::BuildPostings,
         storage::{BuildIndexStorage, IndexStorage},
     },
     traits::{deser::DeSer, postings::IndexPostings},
+    utils::header::{Header, VERSION},
     Index,
 };
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
 /// Dummy "documents" that will get indexed
@@ -113,6 +130,10 @@ where
     fn test(&self) {
         self.test_index();
         self.test_retrieve_iter();
+        self.test_bool_query();
+        self.test_scored();
+        self.test_prefix();
+        self.test_fuzzy();
     }
 
     fn test_index(&self) {
@@ -141,7 +162,127 @@ where
             .by_terms(["text"])
             .unique()
             .get_all::<DefaultRetrieve<_, _, _>>();
-        assert_eq!(res, vec![3, 2, 1]);
+        assert_eq!(res, vec![1, 2, 3]);
+    }
+
+    fn test_prefix(&self) {
+        let dict = self.index.dict();
+
+        let terms: Vec<_> = dict.terms_with_prefix("t").map(|i| i.1).collect();
+        assert_eq!(
+            terms,
+            vec!["term_map(&self)", "text", "this", "tindex", "to"]
+        );
+        assert_eq!(dict.terms_with_prefix("xyz").count(), 0);
+
+        assert_eq!(dict.predecessor("this").unwrap().1, "text");
+        assert_eq!(dict.successor("tindex").unwrap().1, "to");
+        assert_eq!(dict.predecessor(""), None);
+
+        let mut res = self
+            .index
+            .retrieve()
+            .by_prefix("t")
+            .unique()
+            .get_all::<DefaultRetrieve<_, _, _>>();
+        res.sort_unstable();
+        assert_eq!(res, vec![1, 2, 3, 4]);
+
+        let res = self
+            .index
+            .retrieve()
+            .by_prefix("ti")
+            .get_all::<DefaultRetrieve<_, _, _>>();
+        assert_eq!(res, vec![1]);
+    }
+
+    fn test_fuzzy(&self) {
+        let retrieve = self.index.retrieve().by_fuzzy_terms(["txt", "lot"], 1);
+        let text_id = self.index.dict().get_id("text").unwrap();
+        let lol_id = self.index.dict().get_id("lol").unwrap();
+        assert_eq!(retrieve.term_distance(text_id), 1);
+        assert_eq!(retrieve.term_distance(lol_id), 1);
+
+        let mut res = retrieve.unique().get_all::<DefaultRetrieve<_, _, _>>();
+        res.sort_unstable();
+        assert_eq!(res, vec![1, 2, 3]);
+
+        // Exact matches rank higher than fuzzy ones
+        let res = self
+            .index
+            .retrieve()
+            .by_fuzzy_terms(["lol", "othe"], 1)
+            .get_all::<Bm25Retriever<_, _, _>>();
+        let items: Vec<_> = res.iter().map(|i| i.1).collect();
+        assert_eq!(items, vec![3, 2]);
+
+        let res = self
+            .index
+            .retrieve()
+            .by_fuzzy_term("txt", 0)
+            .get_all::<DefaultRetrieve<_, _, _>>();
+        assert!(res.is_empty());
+    }
+
+    fn test_scored(&self) {
+        let res = self
+            .index
+            .retrieve()
+            .by_terms(["text"])
+            .get_all::<Bm25Retriever<_, _, _>>();
+        let items: Vec<_> = res.iter().map(|i| i.1).collect();
+        assert_eq!(items, vec![3, 2, 1]);
+        assert!(res.windows(2).all(|i| i[0].0 >= i[1].0));
+
+        let res = self
+            .index
+            .retrieve()
+            .by_terms(["text", "other"])
+            .with_limit(1)
+            .get_all::<TfIdfRetriever<_, _, _>>();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].1, 2);
+    }
+
+    fn test_bool_query(&self) {
+        let query = |q: BoolQuery<String>| {
+            self.index
+                .retrieve()
+                .by_query(q)
+                .get_all::<BoolRetriever<_, _, _>>()
+        };
+
+        let and = BoolQuery::new()
+            .must("text".to_string())
+            .must("some".to_string());
+        assert_eq!(query(and), vec![1, 2]);
+
+        let not = BoolQuery::new()
+            .must("text".to_string())
+            .must_not("some".to_string());
+        assert_eq!(query(not), vec![3]);
+
+        let or = BoolQuery::new()
+            .should("lol".to_string())
+            .should("other".to_string());
+        assert_eq!(query(or), vec![2, 3]);
+
+        let nested = BoolQuery::new()
+            .must("text".to_string())
+            .must(
+                BoolQuery::new()
+                    .should("lol".to_string())
+                    .should("other".to_string()),
+            )
+            .must_not("other".to_string());
+        assert_eq!(query(nested), vec![3]);
+
+        let res = self
+            .index
+            .retrieve()
+            .by_terms(["text", "some"])
+            .get_all::<BoolRetriever<_, _, _>>();
+        assert_eq!(res, vec![1, 2]);
     }
 }
 
@@ -149,4 +290,389 @@ where
 fn test_builder() {
     new_testset::<_, _, _, Dictionary<_>, compressed::Postings, Storage<_>>().test();
     new_testset::<_, _, _, Dictionary<_>, default::Postings, Storage<_>>().test();
+    new_testset::<_, _, _, Dictionary<_>, tf::Postings, Storage<_>>().test();
+    new_testset::<FstDict, _, _, FstDictBuilder, default::Postings, Storage<_>>().test();
+    new_testset::<FrontCodedDict, _, _, FrontCodedDictBuilder, default::Postings, Storage<_>>()
+        .test();
+}
+
+#[test]
+fn test_bitmap() {
+    let set = new_testset::<_, _, _, Dictionary<_>, bitmap::Postings, Storage<_>>();
+    set.test_index();
+    set.test_retrieve_iter();
+    set.test_bool_query();
+
+    let queries = [
+        BoolQuery::new()
+            .must("text".to_string())
+            .must("some".to_string()),
+        BoolQuery::new()
+            .should("lol".to_string())
+            .should("other".to_string())
+            .must_not("some".to_string()),
+        BoolQuery::new().must_not("text".to_string()),
+    ];
+
+    for q in queries {
+        let exp = set
+            .index
+            .retrieve()
+            .by_query(q.clone())
+            .get_all::<BoolRetriever<_, _, _>>();
+        let res = set
+            .index
+            .retrieve()
+            .by_query(q)
+            .get_all::<BitmapBoolRetriever<_, _, _>>();
+        assert_eq!(res, exp);
+    }
+}
+
+#[test]
+fn test_file_format() {
+    type Compressed =
+        MemBackend<String, u32, Dictionary<String>, Storage<u32>, compressed::Postings>;
+    type Uncompressed =
+        MemBackend<String, u32, Dictionary<String>, Storage<u32>, default::Postings>;
+
+    let set = new_testset::<_, _, _, Dictionary<_>, compressed::Postings, Storage<_>>();
+    let mut encoded = vec![];
+    set.index.encode_to(&mut encoded).unwrap();
+
+    let path = std::env::temp_dir().join(format!("index_format_{}", std::process::id()));
+    std::fs::write(&path, &encoded).unwrap();
+    let opened = Index::<Compressed, String, u32>::open(&path);
+    std::fs::remove_file(&path).unwrap();
+    TestSet {
+        index: opened.unwrap(),
+        term_id_map: set.term_id_map.clone(),
+        item_id_map: set.item_id_map.clone(),
+    }
+    .test();
+
+    let header = Header::read(encoded.as_slice()).unwrap();
+    assert_eq!(header.dict, "dict.default");
+    assert_eq!(header.postings, "postings.compressed");
+    assert_eq!(header.storage, "storage.default");
+
+    // Descriptors claiming huge lengths get rejected before allocating
+    let mut huge = encoded[..8].to_vec();
+    huge.extend(u64::MAX.to_le_bytes());
+    let res = Compressed::read_from(huge.as_slice());
+    assert!(matches!(res, Err(Error::Bincode(_))));
+
+    let res = Uncompressed::read_from(encoded.as_slice());
+    assert!(matches!(
+        res,
+        Err(Error::TypeMismatch {
+            component: "postings",
+            ..
+        })
+    ));
+
+    let mut corrupt = encoded.clone();
+    *corrupt.last_mut().unwrap() ^= 1;
+    let res = Compressed::read_from(corrupt.as_slice());
+    assert!(matches!(res, Err(Error::Corrupt(_))));
+
+    let res = Compressed::read_from(&encoded[..encoded.len() - 1]);
+    assert!(matches!(res, Err(Error::Corrupt(_))));
+
+    let mut other_version = encoded.clone();
+    other_version[4..8].copy_from_slice(&(VERSION + 1).to_le_bytes());
+    let res = Compressed::read_from(other_version.as_slice());
+    assert!(matches!(res, Err(Error::VersionMismatch { .. })));
+
+    let res = Compressed::read_from(&encoded[4..]);
+    assert!(matches!(res, Err(Error::Corrupt(_))));
+
+    // Raw encoding doesn't contain a header
+    let raw = set.index.encode().unwrap();
+    assert!(encoded.ends_with(&raw));
+    let mut streamed = vec![];
+    <Compressed as Backend<_, _>>::encode_into(&set.index, &mut streamed).unwrap();
+    assert_eq!(streamed, raw);
+    let mut dict = vec![];
+    set.index.dict().encode_into(&mut dict).unwrap();
+    assert_eq!(dict, set.index.dict().encode_vec().unwrap());
+
+    assert!(<Compressed as Backend<_, _>>::decode(&raw).is_ok());
+    assert!(Compressed::read_from(raw.as_slice()).is_err());
+}
+
+#[test]
+fn test_external_builder() {
+    type Compressed =
+        MemBackend<String, u32, Dictionary<String>, Storage<u32>, compressed::Postings>;
+
+    let expected = new_testset::<_, _, _, Dictionary<_>, compressed::Postings, Storage<_>>();
+
+    // Budget for 4 mappings to force multiple runs
+    let mut builder: ExternalIndexBuilder<
+        Compressed,
+        _,
+        _,
+        Dictionary<_>,
+        Storage<_>,
+        compressed::Postings,
+    > = ExternalIndexBuilder::with_postings_len(2, 48);
+    builder.add_option(BuildOption::UniquePostings);
+    for (pos, doc) in DOCS.iter().enumerate() {
+        let terms = doc.split(' ').chain(["text"]);
+        builder.index_with_terms(0, pos as u32, terms);
+    }
+    assert!(builder.run_count() > 1);
+    let index = builder.build();
+
+    assert_eq!(index.dict().len(), expected.index.dict().len());
+    assert_eq!(index.posting_count(), 2);
+    assert!(index.postings(1).unwrap().is_empty());
+
+    let postings = index.postings(0).unwrap();
+    let exp_postings = expected.index.postings(0).unwrap();
+    for term in expected.index.dict().iter().filter(|i| i != "text") {
+        let t_id = index.dict().get_id(term.clone()).unwrap();
+        let mut exp = exp_postings.get_posting(expected.index.dict().get_id(term).unwrap());
+        exp.sort_unstable();
+        exp.dedup();
+        assert_eq!(postings.get_posting(t_id), exp);
+    }
+
+    let text = index.dict().get_id("text").unwrap();
+    assert_eq!(
+        postings.get_posting(text),
+        (0..DOCS.len() as u32).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        index.stats().unwrap().posting(0).unwrap().item_count(),
+        DOCS.len() as u32
+    );
+}
+
+#[test]
+fn test_parallel_builder() {
+    type Compressed =
+        MemBackend<String, u32, Dictionary<String>, Storage<u32>, compressed::Postings>;
+    type Builder = ParallelIndexBuilder<
+        Compressed,
+        String,
+        u32,
+        Dictionary<String>,
+        Storage<u32>,
+        compressed::Postings,
+    >;
+
+    let docs = || {
+        DOCS.iter().enumerate().map(|(pos, doc)| {
+            let terms = doc.split(' ').map(|i| i.to_string()).collect();
+            ((pos % 2) as u32, pos as u32, terms)
+        })
+    };
+
+    let mut expected: MemIndexBuilder<_, _, _, Dictionary<_>, Storage<_>, compressed::Postings> =
+        MemIndexBuilder::with_postings_len(2);
+    for (pst_id, item, terms) in docs() {
+        expected.index_with_terms::<_, String>(pst_id, item, terms);
+    }
+    let expected: Index<Compressed, _, _> = expected.build();
+
+    let assert_same = |index: Index<Compressed, String, u32>| {
+        assert_eq!(index.dict().len(), expected.dict().len());
+        for term in expected.dict().iter() {
+            assert_eq!(
+                index.dict().get_id(term.clone()),
+                expected.dict().get_id(term)
+            );
+        }
+
+        for pst_id in 0..2 {
+            let postings = index.postings(pst_id).unwrap();
+            let exp_postings = expected.postings(pst_id).unwrap();
+            for t_id in 0..expected.dict().len() as u32 {
+                assert_eq!(postings.get_posting(t_id), exp_postings.get_posting(t_id));
+            }
+
+            let stats = index.stats().unwrap().posting(pst_id).unwrap();
+            let exp_stats = expected.stats().unwrap().posting(pst_id).unwrap();
+            assert_eq!(stats.item_count(), exp_stats.item_count());
+            assert_eq!(stats.total_len(), exp_stats.total_len());
+        }
+
+        for id in 0..DOCS.len() as u32 {
+            assert_eq!(index.storage().get_item(id), Some(id));
+        }
+    };
+
+    for threads in [1, 3, 8] {
+        let mut builder = Builder::with_postings_len(2);
+        builder.index_par(docs(), threads);
+        assert_same(builder.build());
+    }
+
+    let mut builder = Builder::with_postings_len(2);
+    builder.add_option(BuildOption::UniquePostings);
+    builder.index_par(docs(), 3);
+    let index = builder.build();
+    let text = index.dict().get_id("text").unwrap();
+    assert_eq!(index.postings(1).unwrap().get_posting(text), vec![1, 3]);
+    assert_eq!(index.stats().unwrap().posting(1).unwrap().item_len(3), 3);
+
+    // Shards written from multiple threads merge by their sequence numbers
+    let builder = Builder::with_postings_len(2);
+    std::thread::scope(|s| {
+        for shard in 0..2 {
+            let mut writer = builder.shard();
+            s.spawn(move || {
+                for (seq, (pst_id, item, terms)) in docs().enumerate().rev() {
+                    if seq % 2 == shard {
+                        writer.index::<_, String>(seq as u64, pst_id, item, terms);
+                    }
+                }
+            });
+        }
+    });
+    assert_same(builder.build());
+}
+
+#[test]
+fn test_merge() {
+    merge_test::<compressed::Postings>(false);
+    // Postings containing items only once don't tell the item lengths
+    merge_test::<compressed::Postings>(true);
+    merge_test::<tf::Postings>(false);
+}
+
+fn merge_test<P>(unique: bool)
+where
+    P: IndexPostings + BuildPostings<Output = P, PostingList = Vec<u32>> + DeSer,
+{
+    type Builder<P> = MemIndexBuilder<
+        MemBackend<String, u32, Dictionary<String>, Storage<u32>, P>,
+        String,
+        u32,
+        Dictionary<String>,
+        Storage<u32>,
+        P,
+    >;
+
+    let build = |docs: std::ops::Range<usize>| {
+        let mut builder = Builder::<P>::with_postings_len(2);
+        if unique {
+            builder.add_option(BuildOption::UniquePostings);
+        }
+        for pos in docs {
+            builder.index_with_terms((pos % 2) as u32, pos as u32, DOCS[pos].split(' '));
+        }
+        builder.build()
+    };
+
+    let split = DOCS.len() / 2;
+    let (a, b) = (build(0..split), build(split..DOCS.len()));
+    let expected = build(0..DOCS.len());
+    let merged = a.merge::<Dictionary<_>, Storage<_>, P>(&b).unwrap();
+
+    assert_eq!(merged.dict().len(), expected.dict().len());
+    assert_eq!(merged.posting_count(), 2);
+    let items: Vec<_> = merged.storage().iter().collect();
+    assert_eq!(items, (0..DOCS.len() as u32).collect::<Vec<_>>());
+
+    // Terms of the first index keep their IDs
+    for id in 0..a.dict().len() as u32 {
+        assert_eq!(merged.dict().get_term(id), a.dict().get_term(id));
+    }
+
+    for term in expected.dict().iter() {
+        let t_id = merged.dict().get_id(term.clone()).unwrap();
+        let exp_id = expected.dict().get_id(term.clone()).unwrap();
+        for p_id in 0..2 {
+            let list = merged.postings(p_id).unwrap().get_posting(t_id);
+            assert_eq!(list, expected.postings(p_id).unwrap().get_posting(exp_id));
+        }
+    }
+
+    for p_id in 0..2 {
+        let stats = merged.stats().unwrap().posting(p_id).unwrap();
+        let exp_stats = expected.stats().unwrap().posting(p_id).unwrap();
+        assert_eq!(stats.item_count(), exp_stats.item_count());
+        assert_eq!(stats.total_len(), exp_stats.total_len());
+        for item in 0..DOCS.len() as u32 {
+            assert_eq!(stats.item_len(item), exp_stats.item_len(item));
+        }
+    }
+
+    let res = merged
+        .retrieve()
+        .by_term("text")
+        .in_postings([0, 1])
+        .get_all::<BoolRetriever<_, _, _>>();
+    assert_eq!(res, vec![1, 2, 3]);
+}
+
+/// Item that fails to encode if it is `u32::MAX` and to decode if it is `13`
+#[derive(Debug, PartialEq)]
+struct Flaky(u32);
+
+impl Serialize for Flaky {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        if self.0 == u32::MAX {
+            return Err(ser::Error::custom("can't encode"));
+        }
+        self.0.serialize(ser)
+    }
+}
+
+impl<'de> Deserialize<'de> for Flaky {
+    fn deserialize<D: Deserializer<'de>>(deser: D) -> Result<Self, D::Error> {
+        match u32::deserialize(deser)? {
+            13 => Err(de::Error::custom("can't decode")),
+            i => Ok(Flaky(i)),
+        }
+    }
+}
+
+type FlakyBackend =
+    MemBackend<String, Flaky, Dictionary<String>, Storage<Flaky>, default::Postings>;
+type FlakyBuilder = MemIndexBuilder<
+    FlakyBackend,
+    String,
+    Flaky,
+    Dictionary<String>,
+    Storage<Flaky>,
+    default::Postings,
+>;
+
+#[test]
+fn test_encoding_errors() {
+    let mut builder = FlakyBuilder::new();
+    builder.index_with_terms(0, Flaky(1), ["a"]);
+    builder.index_with_terms(0, Flaky(u32::MAX), ["a"]);
+    assert!(matches!(builder.try_build(), Err(Error::Bincode(_))));
+
+    let mut builder = FlakyBuilder::new();
+    for item in [1, 13, 2] {
+        builder.index_with_terms(0, Flaky(item), ["a"]);
+    }
+    let mut index = builder.try_build().unwrap();
+
+    // Items that can't be decoded get skipped
+    let res = index
+        .retrieve()
+        .by_term("a")
+        .get_all::<DefaultRetrieve<_, _, _>>();
+    assert_eq!(res, vec![Flaky(1), Flaky(2)]);
+    let res = index
+        .retrieve()
+        .by_term("a")
+        .get_results::<BoolRetriever<_, _, _>>();
+    let ids: Vec<_> = res.iter().map(|i| i.item_id).collect();
+    assert_eq!(ids, vec![0, 2]);
+
+    let res = index.add_item(0, Flaky(u32::MAX), vec!["a".to_string()]);
+    assert!(matches!(res, Err(Error::Bincode(_))));
+    assert!(!index.storage().has_item(3));
+    assert_eq!(
+        index.add_item(0, Flaky(3), vec!["b".to_string()]).unwrap(),
+        3
+    );
 }
@@ -0,0 +1,188 @@
+use index_framework::{
+    backend::{
+        memory::presets::{SimpleCompressed, SimpleCompressedBuilder},
+        mmap::{MmapBackend, FORMAT_VERSION},
+    },
+    error::Error,
+    retrieve::{retriever::default::DefaultRetrieve, Retrieve},
+    traits::{
+        backend::{Backend, BackendMut},
+        build::IndexBuilder,
+        dictionary::IndexDictionary,
+        postings::IndexPostings,
+        storage::IndexStorage,
+    },
+    Index,
+};
+
+const DOCS: &[&str] = &[
+    "some text to index",
+    "some other text",
+    "lol text text",
+    "日本語 の テキスト",
+];
+
+fn build() -> Index<SimpleCompressed<String, u32>, String, u32> {
+    let mut builder = SimpleCompressedBuilder::<String, u32>::with_postings_len(2);
+    for (pos, doc) in DOCS.iter().enumerate() {
+        let terms = doc.split(' ').map(|i| i.to_string());
+        builder.index_with_terms((pos % 2) as u32, pos as u32, terms);
+    }
+    builder.build()
+}
+
+#[test]
+fn test_mmap_backend() {
+    let index = build();
+
+    let path = std::env::temp_dir().join(format!("mmap_index_{}", std::process::id()));
+    let file = std::fs::File::create(&path).unwrap();
+    MmapBackend::write_backend(&*index, file).unwrap();
+
+    let mmap: MmapBackend<String, u32> = MmapBackend::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(mmap.dict().len(), index.dict().len());
+    for term in index.dict().iter() {
+        let id = index.dict().get_id(term.clone());
+        assert_eq!(mmap.dict().get_id(term.clone()), id);
+        assert_eq!(mmap.dict().get_term(id.unwrap()), Some(term));
+    }
+    assert_eq!(mmap.dict().get_id("missing"), None);
+
+    assert_eq!(mmap.storage().len(), index.storage().len());
+    for (id, item) in index.storage().iter().enumerate() {
+        assert_eq!(mmap.storage().get_item(id as u32), Some(item));
+    }
+
+    assert_eq!(mmap.posting_count(), index.posting_count());
+    for p_id in 0..index.posting_count() as u32 {
+        for t_id in 0..index.dict().len() as u32 {
+            let exp = index.postings(p_id).unwrap().get_posting(t_id);
+            assert_eq!(mmap.postings(p_id).unwrap().get_posting(t_id), exp);
+        }
+    }
+
+    let decoded = MmapBackend::<String, u32>::decode(&mmap.encode().unwrap()).unwrap();
+    let res = Retrieve::new(&decoded)
+        .by_term("text")
+        .in_postings([0, 1])
+        .unique()
+        .get_all::<DefaultRetrieve<_, _, _>>();
+    assert_eq!(res.len(), 3);
+}
+
+#[test]
+fn test_deleted_items() {
+    let mut index = build();
+    assert!(index.delete_item(0));
+    let mmap = MmapBackend::<String, u32>::from_backend(&*index).unwrap();
+
+    let items: Vec<_> = mmap.storage().iter().collect();
+    assert_eq!(items, vec![1, 2, 3]);
+
+    let text = mmap.dict().get_id("text").unwrap();
+    assert_eq!(mmap.postings(0).unwrap().get_posting(text), vec![1, 1]);
+    assert_eq!(mmap.postings(1).unwrap().get_posting(text), vec![0]);
+    let index_term = mmap.dict().get_id("index").unwrap();
+    assert!(mmap.postings(0).unwrap().get_posting(index_term).is_empty());
+
+    let stats = mmap.stats().unwrap().posting(0).unwrap();
+    assert_eq!(stats.item_count(), 1);
+    assert_eq!(stats.item_len(1), 3);
+}
+
+#[test]
+fn test_open_verified() {
+    let mut file = vec![];
+    MmapBackend::write_backend(&*build(), &mut file).unwrap();
+
+    // Damage a term, which isn't noticed until it gets read
+    let pos = file.windows(3).position(|i| i == b"lol").unwrap();
+    file[pos + 2] = b'm';
+
+    let path = std::env::temp_dir().join(format!("mmap_verified_{}", std::process::id()));
+    std::fs::write(&path, &file).unwrap();
+    let opened = MmapBackend::<String, u32>::open(&path);
+    let verified = MmapBackend::<String, u32>::open_verified(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(opened.unwrap().dict().get_id("lol"), None);
+    assert!(matches!(verified, Err(Error::Corrupt(_))));
+}
+
+#[test]
+fn test_open_errors() {
+    let path = std::env::temp_dir().join("index_framework_missing_index");
+    let res = Index::<SimpleCompressed<String, u32>, String, u32>::open(&path);
+    assert!(matches!(res, Err(Error::Io(_))));
+    assert!(matches!(
+        MmapBackend::<String, u32>::open(&path),
+        Err(Error::Io(_))
+    ));
+
+    assert!(matches!(
+        SimpleCompressed::<String, u32>::decode(&[1, 2, 3]),
+        Err(Error::Bincode(_))
+    ));
+
+    let encoded = MmapBackend::encode_backend(&*build()).unwrap();
+
+    assert!(matches!(
+        MmapBackend::<String, u32>::decode(&[]),
+        Err(Error::MissingComponent(_))
+    ));
+    assert!(matches!(
+        MmapBackend::<String, u32>::decode(&encoded[..encoded.len() - 10]),
+        Err(Error::Corrupt(_))
+    ));
+
+    // The format version directly follows the layout length
+    let mut other_version = encoded;
+    other_version[8..12].copy_from_slice(&99u32.to_le_bytes());
+    assert!(matches!(
+        MmapBackend::<String, u32>::decode(&other_version),
+        Err(Error::VersionMismatch {
+            expected: FORMAT_VERSION,
+            found: 99
+        })
+    ));
+}
+
+#[test]
+fn test_corrupt_data() {
+    let encoded = MmapBackend::encode_backend(&*build()).unwrap();
+    // The dictionary terms table directly follows the layout
+    let header_len = u64::from_le_bytes(encoded[..8].try_into().unwrap()) as usize;
+    let terms_start = 8 + header_len;
+
+    // Entry counts are read from the data and must not overflow offset calculations
+    let mut huge_count = encoded.clone();
+    huge_count[terms_start..terms_start + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(matches!(
+        MmapBackend::<String, u32>::decode(&huge_count),
+        Err(Error::Corrupt(_))
+    ));
+
+    // Terms with invalid UTF-8 can't be decoded but lookups must not panic
+    let mut invalid_term = encoded;
+    let pos = invalid_term.windows(3).position(|i| i == b"lol").unwrap();
+    invalid_term[pos] = 0xFF;
+    let mmap = MmapBackend::<String, u32>::decode(&invalid_term).unwrap();
+    assert_eq!(mmap.dict().get_id("lol"), None);
+    for term in ["some", "text", "other", "index", "missing"] {
+        let _ = mmap.dict().get_id(term);
+    }
+
+    // Searches over the sorted terms skip the unreadable term
+    let dict = mmap.dict();
+    let prefixed: Vec<_> = dict.terms_with_prefix("t").map(|i| i.1).collect();
+    assert_eq!(prefixed, vec!["text", "to"]);
+    let text = dict.get_id("text").unwrap();
+    assert_eq!(dict.fuzzy_terms("txt", 1), vec![(text, 1)]);
+    let to = dict.get_id("to").unwrap();
+    assert_eq!(dict.fuzzy_terms("lo", 1), vec![(to, 1)]);
+    let _ = dict.range("a".to_string()..).count();
+    let _ = dict.predecessor("other");
+    let _ = dict.successor("index");
+}
@@ -0,0 +1,205 @@
+use index_framework::{
+    backend::memory::{
+        dict::fixed_len::FixDict,
+        postings::compressed::Postings,
+        presets::{NGIndex, NGIndexBuilder},
+        storage::default::Storage,
+    },
+    error::Error,
+    retrieve::retriever::ngram::{NGramRetriever, Similarity},
+    traits::{
+        backend::Backend,
+        build::IndexBuilder,
+        dictionary::{IndexDictionary, NGramDictionary},
+    },
+    Index,
+};
+
+const WORDS: &[&str] = &["house", "mouse", "horse", "ho", "日本語"];
+
+fn build(padding: Option<char>) -> Index<NGIndex<3, u32>, String, u32> {
+    let mut builder = NGIndexBuilder::<3, u32>::new();
+    builder.set_ngram_padding(padding);
+    for (pos, word) in WORDS.iter().enumerate() {
+        let id = builder.index_ngrams(0, pos as u32, word);
+        assert_eq!(id, pos as u32);
+    }
+    builder.build()
+}
+
+fn find(index: &Index<NGIndex<3, u32>, String, u32>, query: &str) -> Vec<u32> {
+    let mut res = index
+        .retrieve()
+        .by_ngrams(query)
+        .get::<NGramRetriever<3, _, _, _>>()
+        .map(|i| i.1)
+        .collect::<Vec<_>>();
+    res.sort_unstable();
+    res
+}
+
+#[test]
+fn test_unpadded() {
+    let index = build(None);
+    assert_eq!(index.dict().padding(), None);
+    assert_eq!(index.dict().ngrams("house"), vec!["hou", "ous", "use"]);
+    // Too short for a single n-gram
+    assert!(index.dict().ngrams("ho").is_empty());
+    assert!(index.dict().get_id("ho").is_none());
+
+    assert_eq!(find(&index, "house"), vec![0, 1]);
+    assert_eq!(find(&index, "hors"), vec![2]);
+    assert_eq!(find(&index, "本語"), Vec::<u32>::new());
+    assert_eq!(find(&index, "日本語"), vec![4]);
+    assert!(find(&index, "ho").is_empty());
+    assert!(find(&index, "xyz").is_empty());
+}
+
+#[test]
+fn test_padded() {
+    let index = build(Some('$'));
+    assert_eq!(index.dict().padding(), Some('$'));
+    assert_eq!(index.dict().ngrams("ho"), vec!["$$h", "$ho", "ho$", "o$$"]);
+
+    // Short words get indexed with padding
+    assert_eq!(find(&index, "ho"), vec![0, 2, 3]);
+    assert_eq!(find(&index, "se"), vec![0, 1, 2]);
+    assert_eq!(find(&index, "日"), vec![4]);
+
+    // Duplicate n-grams only count once
+    let res = index
+        .retrieve()
+        .by_ngrams("hohoho")
+        .get_all::<NGramRetriever<3, _, _, _>>();
+    assert_eq!(res.len(), 3);
+    assert!(res.iter().all(|i| i.0 <= 1.0));
+}
+
+#[test]
+fn test_similarity() {
+    let index = build(None);
+    let search = |query: &str, similarity: Similarity, threshold: f32| {
+        index
+            .retrieve()
+            .by_ngrams(query)
+            .get::<NGramRetriever<3, _, _, _>>()
+            .with_similarity(similarity)
+            .with_threshold(threshold)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        search("house", Similarity::Jaccard, 0.0),
+        vec![(1.0, 0), (0.5, 1)]
+    );
+    assert_eq!(
+        search("house", Similarity::Dice, 0.0),
+        vec![(1.0, 0), (2.0 / 3.0, 1)]
+    );
+    assert_eq!(
+        search("house", Similarity::Cosine, 0.0),
+        vec![(1.0, 0), (2.0 / 3.0, 1)]
+    );
+
+    // Items are normalized by their own n-gram count
+    assert_eq!(
+        search("hous", Similarity::Jaccard, 0.0),
+        vec![(2.0 / 3.0, 0), (0.25, 1)]
+    );
+    assert_eq!(
+        search("hous", Similarity::Overlap, 0.0),
+        vec![(1.0, 0), (0.5, 1)]
+    );
+
+    // Query n-grams missing in the index still count
+    assert_eq!(
+        search("housex", Similarity::Jaccard, 0.0),
+        vec![(0.75, 0), (0.4, 1)]
+    );
+
+    assert_eq!(search("house", Similarity::Jaccard, 0.5).len(), 2);
+    assert_eq!(search("house", Similarity::Jaccard, 0.6), vec![(1.0, 0)]);
+
+    let res = index
+        .retrieve()
+        .by_ngrams("house")
+        .with_limit(1)
+        .get_all::<NGramRetriever<3, _, _, _>>();
+    assert_eq!(res, vec![(1.0, 0)]);
+
+    // Setting other terms drops the n-gram count of a previous query
+    let res = index
+        .retrieve()
+        .by_ngrams("housex")
+        .by_terms(["hou", "ous", "use"])
+        .get_all::<NGramRetriever<3, _, _, _>>();
+    assert_eq!(res[0], (1.0, 0));
+    let ids = ["hou", "ous", "use"].map(|i| index.dict().get_id(i).unwrap());
+    let res = index
+        .retrieve()
+        .by_ngrams("housex")
+        .by_term_ids(ids)
+        .get_all::<NGramRetriever<3, _, _, _>>();
+    assert_eq!(res[0], (1.0, 0));
+}
+
+#[test]
+fn test_fuzzy_distances_reset() {
+    let index = build(None);
+    let hou = index.dict().get_id("hou").unwrap();
+    let fuzzy = || index.retrieve().by_fuzzy_term("hox", 1);
+    assert_eq!(fuzzy().term_distance(hou), 1);
+
+    // Setting or adding terms drops their distances of a previous fuzzy query
+    assert_eq!(fuzzy().by_ngrams("house").term_distance(hou), 0);
+    assert_eq!(fuzzy().by_terms(["hou"]).term_distance(hou), 0);
+    assert_eq!(fuzzy().add_term("hou").term_distance(hou), 0);
+
+    // so cursors don't depend on previous queries either
+    let cursor = index
+        .retrieve()
+        .by_ngrams("house")
+        .with_limit(1)
+        .get_page::<NGramRetriever<3, _, _, _>>()
+        .next
+        .unwrap();
+    assert!(fuzzy().by_ngrams("house").with_cursor(cursor).is_ok());
+}
+
+#[test]
+fn test_padding_persists() {
+    let index = build(Some('_'));
+
+    let path = std::env::temp_dir().join(format!("ngram_index_{}", std::process::id()));
+    let mut encoded = vec![];
+    index.encode_to(&mut encoded).unwrap();
+    std::fs::write(&path, &encoded).unwrap();
+    let opened = Index::<NGIndex<3, u32>, String, u32>::open(&path);
+    std::fs::remove_file(&path).unwrap();
+    let opened = opened.unwrap();
+
+    assert_eq!(opened.dict().padding(), Some('_'));
+    assert_eq!(find(&opened, "ho"), find(&index, "ho"));
+    assert_eq!(find(&opened, "mou"), vec![1]);
+}
+
+#[test]
+#[should_panic]
+fn test_padding_after_indexing() {
+    let mut builder = NGIndexBuilder::<3, u32>::new();
+    builder.index_ngrams(0, 0, "house");
+    builder.set_ngram_padding(Some('$'));
+}
+
+#[test]
+fn test_merge_padding() {
+    let index = build(Some('$'));
+    let merged = index
+        .merge::<FixDict<3>, Storage<_>, Postings>(&build(Some('$')))
+        .unwrap();
+    assert_eq!(merged.dict().padding(), Some('$'));
+    assert_eq!(find(&merged, "ho"), vec![0, 0, 2, 2, 3, 3]);
+
+    let res = index.merge::<FixDict<3>, Storage<_>, Postings>(&build(None));
+    assert!(matches!(res, Err(Error::SettingsMismatch(_))));
+}
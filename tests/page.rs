@@ -0,0 +1,182 @@
+use index_framework::{
+    backend::memory::presets::{Positional, PositionalBuilder},
+    error::Error,
+    retrieve::{
+        page::PageCursor,
+        retriever::{
+            boolean::BoolRetriever, default::DefaultRetrieve, phrase::PhraseRetriever,
+            scored::Bm25Retriever, Retriever,
+        },
+        Retrieve,
+    },
+    traits::{backend::BackendMut, build::IndexBuilder},
+    Index,
+};
+
+type Idx = Index<Positional<String, u32>, String, u32>;
+
+const DOCS: &[&str] = &[
+    "red apple",
+    "green apple pie",
+    "red cherry",
+    "apple red wine",
+    "green tea",
+    "red red apple",
+    "apple juice",
+];
+
+fn build() -> Idx {
+    let mut builder = PositionalBuilder::<String, u32>::new();
+    for (pos, doc) in DOCS.iter().enumerate() {
+        builder.index_with_positions(0, pos as u32, doc.split(' '));
+    }
+    builder.build()
+}
+
+/// Walks through all pages of `size` items, passing the cursor on as string
+fn pages<'a, R, F>(index: &'a Idx, size: usize, f: F) -> Vec<Vec<R::Output>>
+where
+    R: Retriever<'a, Positional<String, u32>, String, u32>,
+    F: Fn(
+        Retrieve<'a, Positional<String, u32>, String, u32>,
+    ) -> Retrieve<'a, Positional<String, u32>, String, u32>,
+{
+    let mut out = vec![];
+    let mut retrieve = f(index.retrieve()).with_limit(size);
+    loop {
+        let page = retrieve.get_page::<R>();
+        out.push(page.items);
+
+        let cursor = match page.next {
+            Some(c) => c.to_string().parse::<PageCursor>().unwrap(),
+            None => break,
+        };
+        retrieve = f(index.retrieve()).with_cursor(cursor).unwrap();
+    }
+    out
+}
+
+#[test]
+fn test_default_order() {
+    let index = build();
+    let res = index
+        .retrieve()
+        .by_terms(["green", "red"])
+        .get_all::<DefaultRetrieve<_, _, _>>();
+    assert_eq!(res, vec![1, 4, 0, 2, 3, 5]);
+
+    let res = index
+        .retrieve()
+        .by_terms(["green", "red"])
+        .with_offset(1)
+        .with_limit(3)
+        .get_all::<DefaultRetrieve<_, _, _>>();
+    assert_eq!(res, vec![4, 0, 2]);
+
+    let res = index
+        .retrieve()
+        .by_terms(["green", "red"])
+        .with_offset(10)
+        .get_all::<DefaultRetrieve<_, _, _>>();
+    assert!(res.is_empty());
+}
+
+#[test]
+fn test_pages() {
+    let index = build();
+
+    let all = index
+        .retrieve()
+        .by_term("apple")
+        .get_all::<DefaultRetrieve<_, _, _>>();
+    let res = pages::<DefaultRetrieve<_, _, _>, _>(&index, 2, |r| r.by_term("apple"));
+    assert_eq!(res, vec![vec![0, 1], vec![3, 5], vec![6]]);
+    assert_eq!(res.concat(), all);
+
+    // An exactly filled last page has no cursor
+    let res = pages::<DefaultRetrieve<_, _, _>, _>(&index, 5, |r| r.by_term("apple"));
+    assert_eq!(res, vec![all.clone()]);
+
+    let res = pages::<BoolRetriever<_, _, _>, _>(&index, 2, |r| r.by_terms(["red", "apple"]));
+    assert_eq!(res, vec![vec![0, 3], vec![5]]);
+
+    let res = pages::<PhraseRetriever<_, _, _>, _>(&index, 1, |r| r.by_phrase(["red", "apple"]));
+    assert_eq!(res, vec![vec![0], vec![5]]);
+
+    let all = index
+        .retrieve()
+        .by_terms(["red", "apple"])
+        .get_all::<Bm25Retriever<_, _, _>>();
+    let res = pages::<Bm25Retriever<_, _, _>, _>(&index, 3, |r| r.by_terms(["red", "apple"]));
+    assert_eq!(res.len(), 2);
+    assert_eq!(res.concat(), all);
+
+    // Without a limit, everything is returned in a single page
+    let page = index
+        .retrieve()
+        .by_term("apple")
+        .get_page::<DefaultRetrieve<_, _, _>>();
+    assert_eq!(page.items.len(), 5);
+    assert!(page.next.is_none());
+}
+
+#[test]
+fn test_cursor_format() {
+    assert!("".parse::<PageCursor>().is_err());
+    assert!("12".parse::<PageCursor>().is_err());
+    assert!("a.5".parse::<PageCursor>().is_err());
+    assert!("x.2.ff".parse::<PageCursor>().is_err());
+    assert!("a.5.ff.1".parse::<PageCursor>().is_err());
+    // Cursors always have a limit
+    assert!("a.0.ff".parse::<PageCursor>().is_err());
+
+    let cursor: PageCursor = "a.5.ff".parse().unwrap();
+    assert_eq!(cursor.to_string(), "a.5.ff");
+}
+
+#[test]
+fn test_cursor_validation() {
+    let mut index = build();
+    let page = |index: &Idx| {
+        index
+            .retrieve()
+            .by_term("apple")
+            .with_limit(2)
+            .get_page::<DefaultRetrieve<_, _, _>>()
+    };
+    let cursor = page(&index).next.unwrap();
+    assert!(index
+        .retrieve()
+        .by_term("apple")
+        .with_cursor(cursor)
+        .is_ok());
+
+    // Cursors are bound to their query
+    let res = index.retrieve().by_term("red").with_cursor(cursor);
+    assert!(matches!(res, Err(Error::InvalidCursor)));
+    let res = index
+        .retrieve()
+        .by_term("apple")
+        .unique()
+        .with_cursor(cursor);
+    assert!(matches!(res, Err(Error::InvalidCursor)));
+    let forged: PageCursor = "0.2.0".parse().unwrap();
+    assert!(index
+        .retrieve()
+        .by_term("apple")
+        .with_cursor(forged)
+        .is_err());
+
+    // and to the state of the index
+    index.add_item(0, 7, vec!["apple".to_string()]).unwrap();
+    let res = index.retrieve().by_term("apple").with_cursor(cursor);
+    assert!(matches!(res, Err(Error::InvalidCursor)));
+
+    let cursor = page(&index).next.unwrap();
+    index.delete_item(0);
+    assert!(index
+        .retrieve()
+        .by_term("apple")
+        .with_cursor(cursor)
+        .is_err());
+}
@@ -0,0 +1,54 @@
+use index_framework::{
+    backend::memory::presets::{Positional, PositionalBuilder},
+    retrieve::retriever::phrase::PhraseRetriever,
+    traits::{
+        backend::Backend, build::IndexBuilder, dictionary::IndexDictionary,
+        postings::IndexPositions,
+    },
+    Index,
+};
+
+const DOCS: &[&str] = &[
+    "the quick brown fox",
+    "the brown quick fox",
+    "quick and very brown",
+    "brown fox quick brown",
+];
+
+fn build() -> Index<Positional<String, u32>, String, u32> {
+    let mut builder = PositionalBuilder::<String, u32>::new();
+    for (pos, doc) in DOCS.iter().enumerate() {
+        builder.index_with_positions(0, pos as u32, doc.split(' ').map(|i| i.to_string()));
+    }
+    builder.build()
+}
+
+fn phrase(index: &Index<Positional<String, u32>, String, u32>, p: &str, slop: u32) -> Vec<u32> {
+    index
+        .retrieve()
+        .by_phrase(p.split(' '))
+        .with_slop(slop)
+        .get_all::<PhraseRetriever<_, _, _>>()
+}
+
+#[test]
+fn test_positions() {
+    let index = build();
+    let brown = index.dict().get_id("brown").unwrap();
+    let positions = index.postings(0).unwrap().get_positions(brown);
+    assert_eq!(
+        positions,
+        vec![(0, vec![2]), (1, vec![1]), (2, vec![3]), (3, vec![0, 3])]
+    );
+}
+
+#[test]
+fn test_phrase() {
+    let index = build();
+    assert_eq!(phrase(&index, "quick brown", 0), vec![0, 3]);
+    assert_eq!(phrase(&index, "brown fox", 0), vec![0, 3]);
+    assert_eq!(phrase(&index, "quick brown fox", 0), vec![0]);
+    assert_eq!(phrase(&index, "quick brown", 1), vec![0, 3]);
+    assert_eq!(phrase(&index, "quick brown", 2), vec![0, 2, 3]);
+    assert_eq!(phrase(&index, "quick missing", 5), Vec::<u32>::new());
+}
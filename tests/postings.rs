@@ -1,20 +1,239 @@
 use index_framework::{
-    backend::memory::postings::{compressed, default},
-    traits::postings::{BuildPostings, IndexPostings},
+    backend::memory::postings::{bitmap, compressed, default, packed, tf},
+    traits::postings::{BuildPostings, IndexPostings, PostingCursor},
 };
 use rand::{thread_rng, Rng};
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
 #[test]
 fn test_postings() {
     postings_test::<default::Postings>();
     postings_test::<compressed::Postings>();
+    sorted_postings_test::<packed::Postings>();
+}
+
+#[test]
+fn test_packed_seek() {
+    let list: Vec<u32> = (0..1000).map(|i| i * 7 + (i % 3)).collect();
+    let mut map = HashMap::new();
+    map.insert(4, list.clone());
+    map.insert(9, vec![5, 5, 5]);
+    let postings = packed::Postings::from_map(map);
+
+    assert_eq!(postings.get_posting(4), list);
+    assert_eq!(postings.get_posting(9), vec![5, 5, 5]);
+    assert!(postings.get_posting(5).is_empty());
+
+    for target in [0, 1, 700, 701, 702, 703, 6990, 6993] {
+        let exp = list.iter().find(|i| **i >= target).copied();
+        assert_eq!(postings.first_geq(4, target), exp);
+    }
+    assert_eq!(postings.first_geq(4, u32::MAX), None);
+    assert!(postings.contains(4, 709));
+    assert!(!postings.contains(4, 708));
+}
+
+/// Mirrors the encoded layout of packed postings to create corrupt ones
+#[derive(Serialize, Clone)]
+struct RawPacked {
+    /// `(len, first_block)`
+    lists: Vec<(u32, u32)>,
+    /// `(first, last, bits, offset)`
+    blocks: Vec<(u32, u32, u8, u32)>,
+    data: Vec<u32>,
+}
+
+#[test]
+fn test_packed_corrupt() {
+    let valid = RawPacked {
+        lists: vec![(3, 0)],
+        blocks: vec![(5, 9, 2, 0)],
+        data: vec![0b1010],
+    };
+    let decode = |raw: &RawPacked| -> packed::Postings {
+        bincode::deserialize(&bincode::serialize(raw).unwrap()).unwrap()
+    };
+    assert_eq!(decode(&valid).get_posting(0), vec![5, 7, 9]);
+
+    let corrupt = [
+        // List exceeding the blocks
+        RawPacked {
+            lists: vec![(300, 0)],
+            ..valid.clone()
+        },
+        RawPacked {
+            lists: vec![(3, u32::MAX)],
+            ..valid.clone()
+        },
+        // Invalid bit width
+        RawPacked {
+            blocks: vec![(5, 9, 40, 0)],
+            ..valid.clone()
+        },
+        // Block exceeding the data
+        RawPacked {
+            blocks: vec![(5, 9, 2, 7)],
+            ..valid.clone()
+        },
+        RawPacked {
+            blocks: vec![(5, 9, 32, 0)],
+            ..valid.clone()
+        },
+    ];
+    for raw in corrupt {
+        let postings = decode(&raw);
+        assert!(postings.get_posting(0).is_empty());
+        assert_eq!(postings.cursor(0).count(), 0);
+        assert_eq!(postings.cursor(0).advance_to(6), None);
+        assert_eq!(postings.first_geq(0, 6), None);
+    }
+}
+
+#[test]
+fn test_unmapped_ids() {
+    let mut map = HashMap::new();
+    map.insert(3, vec![1, 2]);
+    map.insert(6, vec![4]);
+    let postings = compressed::Postings::from_map(map);
+
+    for id in [0, 2, 4, 5, 7] {
+        assert!(postings.get_posting(id).is_empty());
+        assert!(!postings.has_id(id));
+        assert_eq!(postings.cursor(id).next(), None);
+    }
+    assert_eq!(postings.get_posting(3), vec![1, 2]);
+    assert_eq!(postings.get_posting(6), vec![4]);
+}
+
+#[test]
+fn test_sparse_len() {
+    sparse_len_test::<compressed::Postings>();
+    sparse_len_test::<packed::Postings>();
+    sparse_len_test::<tf::Postings>();
+    sparse_len_test::<bitmap::Postings>();
+}
+
+fn sparse_len_test<P>()
+where
+    P: BuildPostings<PostingList = Vec<u32>>,
+    P::Output: IndexPostings,
+{
+    let mut map = HashMap::new();
+    map.insert(2, vec![1]);
+    map.insert(40, vec![3, 4]);
+    let postings = P::from_map(map).build();
+
+    assert_eq!(postings.len(), 41);
+    assert_eq!(postings.get_posting(40), vec![3, 4]);
+    assert!(postings.get_posting(39).is_empty());
+}
+
+#[test]
+fn test_cursor() {
+    cursor_test::<default::Postings>();
+    cursor_test::<compressed::Postings>();
+    cursor_test::<packed::Postings>();
+    cursor_test::<tf::Postings>();
+    cursor_test::<bitmap::Postings>();
+}
+
+#[test]
+fn test_term_freqs() {
+    let mut map: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut rand = thread_rng();
+
+    for key in (0..1000).filter(|i| i % 3 != 0) {
+        let size = rand.gen_range(1..50);
+        let data = (0..size).map(|_| rand.gen_range(0..20));
+        map.entry(key).or_default().extend(data);
+    }
+
+    let postings = tf::Postings::from_map(map.clone());
+    let reference = default::Postings::from_map(map.clone());
+
+    for (k, v) in map {
+        let mut freqs = postings.get_term_freqs(k);
+        freqs.sort_unstable();
+        assert_eq!(freqs, reference.get_term_freqs(k));
+
+        let mut unique = v.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(postings.posting_size(k), unique.len());
+    }
+
+    assert!(postings.get_term_freqs(0).is_empty());
+}
+
+/// Tests postings which return their lists sorted
+fn sorted_postings_test<P>()
+where
+    P: IndexPostings + BuildPostings<Output = P, PostingList = Vec<u32>>,
+{
+    let map = random_map();
+
+    let postings = P::from_map(map.clone());
+
+    for (k, mut v) in map {
+        v.sort_unstable();
+        let posts = postings.get_posting(k);
+        assert_eq!(posts, v);
+    }
+}
+
+/// Tests the cursors of postings against `get_posting`
+fn cursor_test<P>()
+where
+    P: IndexPostings + BuildPostings<Output = P, PostingList = Vec<u32>>,
+{
+    let mut map = random_map();
+    map.insert(1_000_000, (0..1000).map(|i| i * 3).collect());
+    for v in map.values_mut() {
+        v.sort_unstable();
+        v.dedup();
+    }
+
+    let postings = P::from_map(map.clone());
+
+    for (k, v) in map {
+        let cursor = postings.cursor(k);
+        assert_eq!(cursor.size_hint().0, v.len());
+        assert_eq!(cursor.collect::<Vec<_>>(), v);
+
+        // Position in `v` after the last item returned by the cursor
+        let mut pos = 0;
+        let mut cursor = postings.cursor(k);
+        for target in [0, 10, 500, 501, 2000, 2999, 400_000, 999_999] {
+            pos += v[pos..].partition_point(|i| *i < target);
+            let exp = v.get(pos).copied();
+            assert_eq!(cursor.advance_to(target), exp);
+            if exp.is_none() {
+                break;
+            }
+            pos += 1;
+            assert_eq!(cursor.size_hint().0, v.len() - pos);
+        }
+    }
+
+    assert_eq!(postings.cursor(u32::MAX).next(), None);
 }
 
 fn postings_test<P>()
 where
     P: IndexPostings + BuildPostings<Output = P, PostingList = Vec<u32>>,
 {
+    let map = random_map();
+
+    let postings = P::from_map(map.clone());
+
+    for (k, v) in map {
+        let posts = postings.get_posting(k);
+        assert_eq!(posts, v);
+    }
+}
+
+fn random_map() -> HashMap<u32, Vec<u32>> {
     let mut map: HashMap<u32, Vec<u32>> = HashMap::new();
     let mut rand = thread_rng();
     let mut did: HashSet<u32> = HashSet::new();
@@ -32,12 +251,7 @@ where
         map.entry(key).or_default().extend(data);
     }
 
-    let postings = P::from_map(map.clone());
-
-    for (k, v) in map {
-        let posts = postings.get_posting(k);
-        assert_eq!(posts, v);
-    }
+    map
 }
 
 /* fn postings_test2<P>()
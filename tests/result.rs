@@ -0,0 +1,172 @@
+use index_framework::{
+    backend::memory::presets::{Simple, SimpleBuilder, TermFreq, TermFreqBuilder},
+    retrieve::{
+        query::BoolQuery,
+        result::{RetrieveResult, TermMatch},
+        retriever::{
+            boolean::BoolRetriever, default::DefaultRetrieve, scored::Bm25Retriever,
+            ResultRetriever, Retriever,
+        },
+        Retrieve,
+    },
+    traits::{backend::Backend, build::IndexBuilder, dictionary::IndexDictionary},
+    Index,
+};
+
+/// Items indexed alternately in postings 0 and 1
+const DOCS: &[&str] = &["a b", "b c", "a c"];
+
+fn build() -> Index<Simple<String, u32>, String, u32> {
+    let mut builder = SimpleBuilder::<String, u32>::with_postings_len(2);
+    for (pos, doc) in DOCS.iter().enumerate() {
+        builder.index_with_terms((pos % 2) as u32, pos as u32 * 10, doc.split(' '));
+    }
+    builder.build()
+}
+
+fn term_match<B: Backend<String, u32>>(index: &B, term: &str, posting_id: u32) -> TermMatch {
+    TermMatch {
+        term_id: index.dict().get_id(term).unwrap(),
+        posting_id,
+    }
+}
+
+#[test]
+fn test_results() {
+    let index = build();
+    let mut res = index
+        .retrieve()
+        .by_terms(["a", "c"])
+        .in_postings([0, 1])
+        .unique()
+        .get_results::<DefaultRetrieve<_, _, _>>();
+    res.sort_by_key(|i| i.item_id);
+
+    let a = term_match(&*index, "a", 0);
+    let c0 = term_match(&*index, "c", 0);
+    let c1 = term_match(&*index, "c", 1);
+    let mut item2 = vec![a, c0];
+    item2.sort_unstable();
+
+    let exp = vec![
+        RetrieveResult {
+            item_id: 0,
+            item: 0,
+            matches: vec![a],
+            score: None,
+        },
+        RetrieveResult {
+            item_id: 1,
+            item: 10,
+            matches: vec![c1],
+            score: None,
+        },
+        RetrieveResult {
+            item_id: 2,
+            item: 20,
+            matches: item2,
+            score: None,
+        },
+    ];
+    assert_eq!(res, exp);
+
+    assert_eq!(res[2].posting_ids(), vec![0]);
+    let mut term_ids = vec![a.term_id, c0.term_id];
+    term_ids.sort_unstable();
+    assert_eq!(res[2].term_ids(), term_ids);
+}
+
+#[test]
+fn test_query_results() {
+    let index = build();
+    let query = BoolQuery::new()
+        .should("a".to_string())
+        .should("c".to_string())
+        .must_not("b".to_string());
+    let res = index
+        .retrieve()
+        .by_query(query)
+        .in_postings([0, 1])
+        .get_results::<BoolRetriever<_, _, _>>();
+
+    // Terms of `must_not` clauses can't match
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].item_id, 2);
+    assert_eq!(res[0].term_ids().len(), 2);
+
+    // Results and items share the same position
+    let mut retriever = index
+        .retrieve()
+        .by_term("a")
+        .get::<BoolRetriever<_, _, _>>();
+    assert_eq!(retriever.next(), Some(0));
+    assert_eq!(retriever.next_result().unwrap().item_id, 2);
+    assert!(retriever.next_result().is_none());
+}
+
+#[test]
+fn test_scored_results() {
+    let mut builder = TermFreqBuilder::<String, u32>::new();
+    for (pos, doc) in ["a a b", "a c", "c"].iter().enumerate() {
+        builder.index_with_terms(0, pos as u32, doc.split(' '));
+    }
+    let index: Index<TermFreq<String, u32>, String, u32> = builder.build();
+
+    let items = index
+        .retrieve()
+        .by_terms(["a", "c"])
+        .get_all::<Bm25Retriever<_, _, _>>();
+    let res = index
+        .retrieve()
+        .by_terms(["a", "c"])
+        .get::<Bm25Retriever<_, _, _>>()
+        .results()
+        .collect::<Vec<_>>();
+
+    assert_eq!(res.len(), items.len());
+    for (res, (score, item)) in res.iter().zip(items) {
+        assert_eq!(res.score, Some(score));
+        assert_eq!(res.item, item);
+        assert_eq!(res.item_id, item);
+    }
+    assert_eq!(res[0].term_ids().len(), 2);
+}
+
+/// Custom retriever only implementing the base `Retriever` trait, yielding `0..limit`
+struct Counter {
+    next: u32,
+    limit: u32,
+}
+
+impl<'a> Retriever<'a, Simple<String, u32>, String, u32> for Counter {
+    type Output = u32;
+
+    fn new(retr: Retrieve<'a, Simple<String, u32>, String, u32>) -> Self {
+        Self {
+            next: 0,
+            limit: retr.limit() as u32,
+        }
+    }
+
+    fn q_term_ids(&self) -> &[u32] {
+        &[]
+    }
+}
+
+impl Iterator for Counter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.next < self.limit).then(|| {
+            self.next += 1;
+            self.next - 1
+        })
+    }
+}
+
+#[test]
+fn test_custom_retriever() {
+    let index = build();
+    let res = index.retrieve().with_limit(3).get_all::<Counter>();
+    assert_eq!(res, vec![0, 1, 2]);
+}
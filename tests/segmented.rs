@@ -0,0 +1,223 @@
+use index_framework::{
+    backend::memory::{
+        build::MemIndexBuilder, dict::default::Dictionary, postings::default::Postings,
+        presets::PositionalBuilder, storage::default::Storage, MemBackend,
+    },
+    retrieve::retriever::{
+        default::DefaultRetrieve, phrase::PhraseRetriever, scored::Bm25Retriever,
+    },
+    segmented::{
+        merge::{MergePolicy, NoMergePolicy, TieredMergePolicy},
+        SegmentedIndex,
+    },
+    traits::{
+        backend::{Backend, BackendMut},
+        build::IndexBuilder,
+        dictionary::IndexDictionary,
+        storage::IndexStorage,
+    },
+    Index,
+};
+
+type Mem = MemBackend<String, u32, Dictionary<String>, Storage<u32>, Postings>;
+type Builder = MemIndexBuilder<Mem, String, u32, Dictionary<String>, Storage<u32>, Postings>;
+
+const DOCS: &[&str] = &[
+    "the quick brown fox",
+    "the lazy dog",
+    "quick brown dogs",
+    "a brown cat",
+    "cats and dogs",
+    "the brown bear",
+    "a quick cat",
+    "lazy brown cows",
+];
+
+/// Builds a segment of the documents in `range`, storing their positions as items
+fn segment(range: std::ops::Range<usize>) -> Index<Mem, String, u32> {
+    let mut builder = Builder::new();
+    for pos in range {
+        builder.index_with_terms(0, pos as u32, DOCS[pos].split(' '));
+    }
+    builder.build()
+}
+
+fn find(index: &SegmentedIndex<Mem, String, u32>, term: &str) -> Vec<u32> {
+    index
+        .snapshot()
+        .search::<DefaultRetrieve<_, _, _>, _>(|r| r.by_term(term).unique())
+}
+
+/// Segmented index with a segment for each pair of documents
+fn build(policy: impl MergePolicy + 'static) -> SegmentedIndex<Mem, String, u32> {
+    let index = SegmentedIndex::with_merge_policy(Builder::new, policy);
+    for start in (0..DOCS.len()).step_by(2) {
+        index.add_segment(segment(start..start + 2));
+    }
+    index
+}
+
+fn sorted(mut v: Vec<u32>) -> Vec<u32> {
+    v.sort_unstable();
+    v
+}
+
+#[test]
+fn test_fan_out() {
+    let index = build(NoMergePolicy);
+    assert_eq!(index.segment_count(), 4);
+    assert!(!index.merge());
+
+    assert_eq!(sorted(find(&index, "brown")), vec![0, 2, 3, 5, 7]);
+    assert_eq!(sorted(find(&index, "dogs")), vec![2, 4]);
+    assert!(find(&index, "unicorn").is_empty());
+
+    let snapshot = index.snapshot();
+    assert_eq!(snapshot.len(), DOCS.len());
+    let limited =
+        snapshot.search::<DefaultRetrieve<_, _, _>, _>(|r| r.by_term("brown").with_limit(3));
+    assert_eq!(limited.len(), 3);
+
+    // Offsets apply to the results of all segments
+    let page = snapshot
+        .search::<DefaultRetrieve<_, _, _>, _>(|r| r.by_term("brown").with_offset(2).with_limit(2));
+    assert_eq!(page, vec![3, 5]);
+    let page =
+        snapshot.search::<DefaultRetrieve<_, _, _>, _>(|r| r.by_term("brown").with_offset(4));
+    assert_eq!(page, vec![7]);
+    let scored = snapshot.search_scored::<Bm25Retriever<_, _, _>, _>(|r| r.by_term("brown"));
+    let page = snapshot.search_scored::<Bm25Retriever<_, _, _>, _>(|r| {
+        r.by_term("brown").with_offset(1).with_limit(3)
+    });
+    assert_eq!(page, scored[1..4]);
+
+    // Terms get global IDs in the order they appear first
+    let the = snapshot.term_id(&"the".to_string()).unwrap();
+    assert_eq!(the, 0);
+    let cat = snapshot.term_id(&"cat".to_string()).unwrap();
+    assert_eq!(snapshot.term(cat).as_deref(), Some("cat"));
+
+    for segment in snapshot.segments() {
+        for local in 0..segment.index().dict().len() as u32 {
+            let global = segment.global_term_id(local).unwrap();
+            assert_eq!(segment.local_term_id(global), Some(local));
+            assert_eq!(
+                segment.index().dict().get_term(local),
+                snapshot.term(global)
+            );
+        }
+    }
+
+    let res = snapshot.search_term_ids::<DefaultRetrieve<_, _, _>, _>(&[cat], |r| r);
+    assert_eq!(sorted(res), vec![3, 6]);
+
+    let scored = snapshot
+        .search_scored::<Bm25Retriever<_, _, _>, _>(|r| r.by_terms(["quick", "cat"]).with_limit(2));
+    assert_eq!(scored.len(), 2);
+    assert!(scored[0].0 >= scored[1].0);
+    assert_eq!(scored[0].1, 6);
+}
+
+#[test]
+fn test_merge() {
+    let index = build(TieredMergePolicy::new(2, 1));
+    let before = index.snapshot();
+    let term_ids: Vec<_> = DOCS
+        .iter()
+        .flat_map(|i| i.split(' '))
+        .map(|i| before.term_id(&i.to_string()))
+        .collect();
+
+    assert_eq!(index.merge_all(), 3);
+    assert_eq!(index.segment_count(), 1);
+
+    // Older snapshots keep their segments
+    assert_eq!(before.segments().len(), 4);
+
+    let after = index.snapshot();
+    assert_eq!(after.len(), DOCS.len());
+    assert_eq!(after.term_count(), before.term_count());
+    let new_ids: Vec<_> = DOCS
+        .iter()
+        .flat_map(|i| i.split(' '))
+        .map(|i| after.term_id(&i.to_string()))
+        .collect();
+    assert_eq!(term_ids, new_ids);
+
+    // Items keep their order
+    let items: Vec<_> = after.segments()[0].index().storage().iter().collect();
+    assert_eq!(items, (0..DOCS.len() as u32).collect::<Vec<_>>());
+    assert_eq!(sorted(find(&index, "brown")), vec![0, 2, 3, 5, 7]);
+    assert_eq!(sorted(find(&index, "lazy")), vec![1, 7]);
+    let stats = after.segments()[0].index().stats().unwrap();
+    assert_eq!(stats.posting(0).unwrap().item_count(), DOCS.len() as u32);
+}
+
+#[test]
+fn test_merge_positions() {
+    let index = SegmentedIndex::with_merge_policy(
+        PositionalBuilder::<String, u32>::new,
+        TieredMergePolicy::new(2, 1),
+    );
+    for start in [0, 2] {
+        let mut builder = PositionalBuilder::<String, u32>::new();
+        for (pos, doc) in DOCS.iter().enumerate().skip(start).take(2) {
+            let terms = doc.split(' ').map(|i| i.to_string());
+            builder.index_with_positions(0, pos as u32, terms);
+        }
+        index.add_segment(builder.build());
+    }
+
+    let phrase = |p: &str| {
+        index
+            .snapshot()
+            .search::<PhraseRetriever<_, _, _>, _>(|r| r.by_phrase(p.split(' ')))
+    };
+    assert_eq!(phrase("quick brown"), vec![0, 2]);
+    assert!(index.merge());
+    assert_eq!(index.segment_count(), 1);
+    assert_eq!(phrase("quick brown"), vec![0, 2]);
+    assert_eq!(phrase("brown dogs"), vec![2]);
+    assert!(phrase("brown quick").is_empty());
+}
+
+#[test]
+fn test_merge_deleted() {
+    let index = SegmentedIndex::with_merge_policy(Builder::new, TieredMergePolicy::new(2, 1));
+    let mut first = segment(0..2);
+    first.delete_item(0);
+    index.add_segment(first);
+    index.add_segment(segment(2..4));
+
+    assert_eq!(sorted(find(&index, "brown")), vec![2, 3]);
+    assert!(index.merge());
+    assert_eq!(index.snapshot().len(), 3);
+    assert_eq!(sorted(find(&index, "brown")), vec![2, 3]);
+    // Terms only used by deleted items keep their global ID
+    assert!(index.snapshot().term_id(&"fox".to_string()).is_some());
+}
+
+#[test]
+fn test_background_merge() {
+    let index = build(TieredMergePolicy::new(2, 1));
+    let handle = index.spawn_merge().unwrap();
+
+    // Queries and new segments don't have to wait for the merge
+    assert_eq!(sorted(find(&index, "brown")), vec![0, 2, 3, 5, 7]);
+    index.add_segment(segment(0..1));
+
+    handle.join().unwrap();
+    index.merge_all();
+    assert!(index.segment_count() <= 2);
+    assert_eq!(sorted(find(&index, "fox")), vec![0, 0]);
+}
+
+#[test]
+fn test_tiered_policy() {
+    let policy = TieredMergePolicy::new(3, 10);
+    assert_eq!(policy.find_merge(&[5, 5]), None);
+    assert_eq!(policy.find_merge(&[5, 5, 5]), Some(0..3));
+    assert_eq!(policy.find_merge(&[100, 5, 20, 5, 5, 5]), Some(3..6));
+    assert_eq!(policy.find_merge(&[40, 50, 60, 5]), Some(0..3));
+    assert_eq!(policy.find_merge(&[40, 5, 50, 5, 60]), None);
+}
@@ -0,0 +1,282 @@
+use index_framework::{
+    backend::memory::{
+        build::MemIndexBuilder,
+        dict::default::Dictionary,
+        postings::{bitmap, compressed, default::Postings, positional, tf},
+        presets::{Positional, PositionalBuilder},
+        storage::default::Storage,
+        MemBackend,
+    },
+    retrieve::retriever::{
+        boolean::BoolRetriever, default::DefaultRetrieve, phrase::PhraseRetriever,
+        scored::Bm25Retriever,
+    },
+    traits::{
+        backend::{Backend, BackendMut},
+        build::IndexBuilder,
+        dictionary::IndexDictionary,
+        postings::{IndexPositions, IndexPostings, PostingsMut},
+        storage::IndexStorage,
+    },
+    Index,
+};
+use serde::Serialize;
+
+type Mutable = MemBackend<String, u32, Dictionary<String>, Storage<u32>, Postings>;
+
+const DOCS: &[&str] = &[
+    "the quick brown fox",
+    "the lazy dog",
+    "quick brown dogs",
+    "a brown cat",
+];
+
+/// Builds an index storing `10 * pos` as item for each document
+fn build() -> Index<Mutable, String, u32> {
+    let mut builder: MemIndexBuilder<_, _, _, Dictionary<_>, Storage<_>, Postings> =
+        MemIndexBuilder::new();
+    for (pos, doc) in DOCS.iter().enumerate() {
+        builder.index_with_terms(0, pos as u32 * 10, doc.split(' '));
+    }
+    builder.build()
+}
+
+fn find(index: &Index<Mutable, String, u32>, term: &str) -> Vec<u32> {
+    let mut res = index
+        .retrieve()
+        .by_term(term)
+        .get_all::<DefaultRetrieve<_, _, _>>();
+    res.sort_unstable();
+    res
+}
+
+#[test]
+fn test_add_item() {
+    let mut index = build();
+    let dict_len = index.dict().len();
+
+    let id = index
+        .add_item(0, 40, vec!["brown".to_string(), "owl".to_string()])
+        .unwrap();
+    assert_eq!(id, 4);
+    assert_eq!(index.dict().len(), dict_len + 1);
+    assert_eq!(index.storage().get_item(id), Some(40));
+
+    assert_eq!(find(&index, "owl"), vec![40]);
+    assert_eq!(find(&index, "brown"), vec![0, 20, 30, 40]);
+    assert_eq!(index.stats().unwrap().posting(0).unwrap().item_len(id), 2);
+
+    // Existing terms keep their IDs
+    let fox = index.dict().get_id("fox").unwrap();
+    index
+        .add_item(0, 50, vec!["fox".to_string(), "fox".to_string()])
+        .unwrap();
+    assert_eq!(index.dict().get_id("fox"), Some(fox));
+    assert_eq!(find(&index, "fox"), vec![0, 50]);
+    assert_eq!(index.stats().unwrap().posting(0).unwrap().item_len(5), 2);
+}
+
+#[test]
+fn test_delete_item() {
+    let mut index = build();
+
+    assert!(index.delete_item(2));
+    assert!(!index.delete_item(2));
+    assert!(!index.delete_item(100));
+    assert!(index.is_deleted(2));
+    assert_eq!(index.deleted_count(), 1);
+
+    assert_eq!(find(&index, "brown"), vec![0, 30]);
+    assert_eq!(find(&index, "dogs"), Vec::<u32>::new());
+
+    let res = index
+        .retrieve()
+        .by_terms(["quick", "brown"])
+        .get_all::<BoolRetriever<_, _, _>>();
+    assert_eq!(res, vec![0]);
+
+    let res: Vec<_> = index
+        .retrieve()
+        .by_term("quick")
+        .get::<Bm25Retriever<_, _, _>>()
+        .map(|i| i.1)
+        .collect();
+    assert_eq!(res, vec![0]);
+
+    let stats = index.stats().unwrap().posting(0).unwrap();
+    assert_eq!(stats.item_count(), 3);
+    assert_eq!(stats.item_len(2), 0);
+}
+
+#[test]
+fn test_compact() {
+    let mut index = build();
+    index
+        .add_item(0, 40, vec!["brown".to_string(), "owl".to_string()])
+        .unwrap();
+    index.delete_item(0);
+    index.delete_item(2);
+
+    assert_eq!(index.compact(), 2);
+    assert_eq!(index.compact(), 0);
+    assert_eq!(index.deleted_count(), 0);
+
+    let items: Vec<_> = index.storage().iter().collect();
+    assert_eq!(items, vec![10, 30, 40]);
+
+    let postings = index.postings(0).unwrap();
+    let brown = index.dict().get_id("brown").unwrap();
+    assert_eq!(postings.get_posting(brown), vec![1, 2]);
+    let dog = index.dict().get_id("dog").unwrap();
+    assert_eq!(postings.get_posting(dog), vec![0]);
+    // Lists left empty get dropped
+    let fox = index.dict().get_id("fox").unwrap();
+    assert!(!postings.has_id(fox));
+
+    let stats = index.stats().unwrap().posting(0).unwrap();
+    assert_eq!(stats.item_count(), 3);
+    assert_eq!(stats.item_len(0), 3);
+    assert_eq!(stats.item_len(2), 2);
+
+    assert_eq!(find(&index, "brown"), vec![30, 40]);
+    assert_eq!(index.add_item(0, 50, vec!["owl".to_string()]).unwrap(), 3);
+    assert_eq!(find(&index, "owl"), vec![40, 50]);
+}
+
+#[test]
+fn test_bitmap_postings_mut() {
+    let mut postings = bitmap::Postings::new();
+    postings.add_item(1, 5);
+    postings.add_item(1, 2);
+    postings.add_item(3, 2);
+    assert_eq!(postings.get_posting(1), vec![2, 5]);
+
+    postings.remap_items(|i| (i != 2).then_some(i - 1));
+    assert_eq!(postings.get_posting(1), vec![4]);
+    assert!(!postings.has_id(3));
+}
+
+#[test]
+fn test_postings_mut() {
+    postings_mut_test::<compressed::Postings>();
+    postings_mut_test::<tf::Postings>();
+    postings_mut_test::<positional::Postings>();
+}
+
+fn postings_mut_test<P: PostingsMut + Default>() {
+    let mut postings = P::default();
+    postings.add_item(3, 2);
+    postings.add_item(1, 2);
+    postings.add_item(1, 5);
+    postings.add_item(6, 7);
+    assert_eq!(postings.get_posting(1), vec![2, 5]);
+    assert_eq!(postings.get_posting(3), vec![2]);
+    assert_eq!(postings.get_posting(6), vec![7]);
+    for id in [0, 2, 4, 5, 7] {
+        assert!(!postings.has_id(id));
+    }
+
+    postings.remap_items(|i| (i != 2).then_some(i - 1));
+    assert_eq!(postings.get_posting(1), vec![4]);
+    assert_eq!(postings.get_posting(6), vec![6]);
+    assert!(!postings.has_id(3));
+}
+
+#[test]
+fn test_postings_mut_garbage() {
+    postings_garbage_test::<compressed::Postings>();
+    postings_garbage_test::<tf::Postings>();
+    postings_garbage_test::<positional::Postings>();
+}
+
+/// Lists replaced by `add_item` must not pile up when no item gets deleted
+fn postings_garbage_test<P: PostingsMut + Default + Serialize>() {
+    let mut postings = P::default();
+    for item in 0..1000 {
+        postings.add_item(item % 3, item);
+    }
+    assert_eq!(postings.get_posting(1).len(), 333);
+    let size = bincode::serialized_size(&postings).unwrap();
+
+    postings.remap_items(Some);
+    let compacted = bincode::serialized_size(&postings).unwrap();
+    assert!(size <= compacted * 3, "{size} > 3 * {compacted}");
+}
+
+#[test]
+fn test_positions_mut() {
+    let mut postings = tf::Postings::new();
+    postings.add_item_positioned(2, 1, &[0, 4]);
+    postings.add_item(2, 3);
+    assert_eq!(postings.get_term_freqs(2), vec![(1, 2), (3, 1)]);
+
+    let mut postings = positional::Postings::new();
+    postings.add_item_positioned(2, 1, &[4, 0]);
+    postings.add_item_positioned(2, 3, &[1]);
+    postings.remap_items(Some);
+    assert_eq!(
+        postings.get_positions(2),
+        vec![(1, vec![0, 4]), (3, vec![1])]
+    );
+}
+
+#[test]
+fn test_phrase_after_update() {
+    let mut builder = PositionalBuilder::<String, u32>::new();
+    for (pos, doc) in DOCS.iter().enumerate() {
+        builder.index_with_positions(0, pos as u32, doc.split(' ').map(|i| i.to_string()));
+    }
+    let mut index: Index<Positional<String, u32>, String, u32> = builder.build();
+
+    let terms = |doc: &str| doc.split(' ').map(|i| i.to_string()).collect();
+    index.add_item(0, 4, terms("the brown quick fox")).unwrap();
+    index.add_item(0, 5, terms("a quick brown owl")).unwrap();
+    index.delete_item(0);
+
+    let phrase = |index: &Index<Positional<String, u32>, String, u32>, p: &str| {
+        let mut res = index
+            .retrieve()
+            .by_phrase(p.split(' '))
+            .get_all::<PhraseRetriever<_, _, _>>();
+        res.sort_unstable();
+        res
+    };
+    assert_eq!(phrase(&index, "quick brown"), vec![2, 5]);
+    assert_eq!(phrase(&index, "brown quick fox"), vec![4]);
+
+    index.compact();
+    assert_eq!(phrase(&index, "quick brown"), vec![2, 5]);
+    assert_eq!(phrase(&index, "brown owl"), vec![5]);
+}
+
+#[test]
+fn test_merge_positions() {
+    let build = |docs: std::ops::Range<usize>| {
+        let mut builder = PositionalBuilder::<String, u32>::new();
+        for pos in docs {
+            builder.index_with_positions(
+                0,
+                pos as u32,
+                DOCS[pos].split(' ').map(|i| i.to_string()),
+            );
+        }
+        builder.build()
+    };
+    let mut a: Index<Positional<String, u32>, String, u32> = build(0..2);
+    let b = build(2..DOCS.len());
+    a.delete_item(1);
+
+    let merged = a
+        .merge::<Dictionary<_>, Storage<_>, positional::PositionalBuilder>(&b)
+        .unwrap();
+    let brown = merged.dict().get_id("brown").unwrap();
+    let positions = merged.postings(0).unwrap().get_positions(brown);
+    assert_eq!(positions, vec![(0, vec![2]), (1, vec![1]), (2, vec![1])]);
+
+    let mut res = merged
+        .retrieve()
+        .by_phrase("quick brown".split(' '))
+        .get_all::<PhraseRetriever<_, _, _>>();
+    res.sort_unstable();
+    assert_eq!(res, vec![0, 2]);
+}